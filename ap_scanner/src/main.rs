@@ -1,54 +1,458 @@
 mod scanning;
-use clap::Parser;
-use scanning::{daemon::daemon_service, reading::Reading};
+use clap::{Args, Parser, Subcommand};
+use scanning::{
+    benchmark::benchmark,
+    daemon::{daemon_service, fetch, upload, ServerError},
+    diff::{Diff, THRESHOLD_DB},
+    fleet::Fleet,
+    matrix::InterferenceMatrix,
+    measure::Measure,
+    planner::Strategy,
+    reading::Reading,
+    regulatory::Domain,
+    render::{Chart, Format, Renderer},
+    scanner::{IwScanner, Scanner},
+    site::Site,
+    source::{measure, ReplaySource, ScanSource, SyntheticSource},
+};
+
+// exit codes, clap exits with 2 on invalid usage
+const EXIT_FAILURE: i32 = 1;
+const EXIT_SERVER: i32 = 3;
+
+// where readings are uploaded by default
+const SERVER: &str = "http://0.0.0.0:9999/";
+
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
-struct Args {
-    #[clap(short, long)]
-    /// load a measure from a json file
-    load: Option<String>,
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
 
-    #[clap(short, long)]
-    /// save a measure to a json file
-    save: Option<String>,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Scan the networks in range and suggest channels for them
+    Scan {
+        #[clap(short, long)]
+        /// local where the measure is taken
+        place: String,
 
-    #[clap(short, long)]
-    /// local where the measure was taken
-    place: Option<String>,
+        #[clap(short, long)]
+        /// save the measure to a json file, to analyse it again later
+        save: Option<String>,
+
+        #[clap(flatten)]
+        source: SourceArgs,
+
+        #[clap(flatten)]
+        plan: PlanArgs,
+
+        #[clap(flatten)]
+        output: OutputArgs,
+    },
+    /// Analyse a saved measure again, or the networks of a saved reading
+    Analyze {
+        /// measure saved with `scan --save`, or a reading saved with `--analysis`
+        file: String,
+
+        #[clap(flatten)]
+        plan: PlanArgs,
+
+        #[clap(flatten)]
+        output: OutputArgs,
+    },
+    /// Show what changed between two measures of the same local
+    Diff {
+        /// measure or reading taken first, or the id the server keeps a reading under
+        before: String,
+
+        /// measure or reading taken last, or the id the server keeps a reading under
+        after: String,
 
+        #[clap(long, default_value_t = THRESHOLD_DB, validator = signal_change)]
+        /// smallest signal change to report, in dB
+        threshold: f32,
+
+        #[clap(long)]
+        /// print json instead of text
+        json: bool,
+
+        #[clap(long, default_value = SERVER)]
+        /// server to download readings from
+        server: String,
+    },
+    /// Upload a saved reading to the server
+    Upload {
+        /// reading saved with `--analysis`
+        file: String,
+
+        #[clap(long, default_value = SERVER)]
+        /// server to upload to
+        server: String,
+    },
+    /// Scan every 5 minutes and upload each reading to the server
+    Daemon {
+        #[clap(short, long)]
+        /// local where the measures are taken
+        place: String,
+
+        #[clap(long, default_value = SERVER)]
+        /// server to upload to
+        server: String,
+
+        #[clap(flatten)]
+        plan: PlanArgs,
+    },
+    /// Plan a whole site from the readings saved in a directory
+    Survey {
+        /// directory holding the readings
+        dir: String,
+
+        #[clap(short, long)]
+        /// which place to plan when the directory holds readings of several
+        place: Option<String>,
+
+        #[clap(flatten)]
+        plan: PlanArgs,
+
+        #[clap(flatten)]
+        output: OutputArgs,
+    },
+    /// Compare the planners over synthetic sites
+    Benchmark {
+        /// how many sites to plan
+        rounds: usize,
+
+        #[clap(long, default_value_t = 40)]
+        /// networks of each site
+        networks: usize,
+
+        #[clap(long)]
+        /// seed of the sites and of the planners, to repeat a run; random by default
+        seed: Option<u64>,
+    },
+}
+
+/// Where the networks come from.
+#[derive(Args, Debug)]
+struct SourceArgs {
     #[clap(short, long)]
-    daemon: bool,
+    /// replay a captured `iw scan` output or a json list of networks instead of scanning
+    replay: Option<String>,
+
+    #[clap(long, requires = "replay")]
+    /// replay a captured `iw survey dump` output or a json list of channel surveys along with the scan
+    survey: Option<String>,
+
+    #[clap(long, conflicts_with = "replay")]
+    /// generate this many random networks instead of scanning
+    synthetic: Option<usize>,
+
+    #[clap(long, conflicts_with_all = &["replay", "synthetic"])]
+    /// scan through the `iw` binary instead of nl80211
+    iw: bool,
 }
 
-fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
+impl SourceArgs {
+    fn source(&self, seed: u64) -> Box<dyn ScanSource> {
+        if let Some(replay) = &self.replay {
+            let source = ReplaySource::new(replay);
 
-    if args.daemon {
-        if let Some(place) = args.place {
-            daemon_service(place);
-            return Ok(());
+            match &self.survey {
+                Some(survey) => Box::new(source.with_survey(survey)),
+                None => Box::new(source),
+            }
+        } else if let Some(count) = self.synthetic {
+            Box::new(SyntheticSource::new(count, seed))
+        } else if self.iw {
+            Box::new(IwScanner)
         } else {
-            println!("Please specify a place");
+            Box::new(Scanner)
         }
     }
+}
 
-    if let Some(place) = args.place {
-        let reading = Reading::new(place)?;
+/// How the networks are planned.
+#[derive(Args, Debug)]
+struct PlanArgs {
+    #[clap(short, long, validator = known_country)]
+    /// two letter country code whose channel rules to follow, detected from the scan by default
+    country: Option<String>,
 
-        if let Some(save) = args.save {
-            reading.serialize(save)?;
-        } else {
-            println!("{:#?}", reading);
+    #[clap(long, multiple_occurrences = true)]
+    /// BSSID or SSID of a network we manage, only those get suggestions and the rest stay put
+    own: Vec<String>,
 
-            reading.output_analysis()?;
+    #[clap(long)]
+    /// file listing the BSSIDs or SSIDs we manage, one per line
+    own_file: Option<String>,
+
+    #[clap(long, arg_enum, default_value_t)]
+    /// how channels are handed out
+    planner: Strategy,
+
+    #[clap(long)]
+    /// seed of every random choice behind the suggestions, and of synthetic networks, to
+    /// reproduce those of a saved reading; random by default
+    seed: Option<u64>,
+}
+
+impl PlanArgs {
+    fn fleet(&self) -> anyhow::Result<Fleet> {
+        let mut fleet = Fleet::new(self.own.clone());
+
+        if let Some(own_file) = &self.own_file {
+            fleet.load(own_file)?;
         }
-    } else if let Some(load) = args.load {
-        let measure = Reading::deserialize(load)?;
 
-        println!("{:#?}", measure);
-    } else {
-        println!("Run ./ap_scanner --help for more information.");
+        Ok(fleet)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed.unwrap_or_else(rand::random)
     }
 
-    Ok(())
+    /// The country asked for, countries without rules of their own follow the world rules.
+    fn country(&self) -> Option<String> {
+        if let Some(country) = &self.country {
+            if Domain::for_country(country).is_none() {
+                warn(format_args!(
+                    "No channel rules for country {:?}, following the world rules.",
+                    country
+                ));
+            }
+        }
+
+        self.country.clone()
+    }
+
+    fn analyse(&self, measure: Measure) -> anyhow::Result<Reading> {
+        let planner = self.planner.planner();
+
+        measure.analyse(
+            self.country(),
+            &self.fleet()?,
+            planner.as_ref(),
+            self.seed(),
+        )
+    }
+}
+
+/// What is done with the reading.
+#[derive(Args, Debug)]
+struct OutputArgs {
+    #[clap(short, long)]
+    /// save the reading, the analysis, to a json file instead of printing it
+    analysis: Option<String>,
+
+    #[clap(long)]
+    /// export the interference between every pair of networks of the reading, as CSV when the
+    /// file ends in .csv and as json otherwise
+    matrix: Option<String>,
+
+    #[clap(long, arg_enum, default_value_t, conflicts_with = "analysis")]
+    /// format the analysis is printed in
+    format: Format,
+
+    #[clap(long, conflicts_with = "analysis")]
+    /// draw the networks on their suggested channels as well, with `--format chart`
+    suggested: bool,
+}
+
+impl OutputArgs {
+    fn output(self, reading: &Reading) -> anyhow::Result<()> {
+        if self.suggested && self.format != Format::Chart {
+            warn("--suggested is only drawn with --format chart.");
+        }
+
+        if let Some(matrix) = self.matrix {
+            InterferenceMatrix::new(reading).save(matrix)?;
+        }
+
+        match self.analysis {
+            Some(analysis) => reading.serialize(analysis),
+            None => {
+                let renderer: Box<dyn Renderer> = match self.format {
+                    Format::Chart => Box::new(Chart {
+                        suggested: self.suggested,
+                    }),
+                    format => format.renderer(),
+                };
+
+                print!("{}", renderer.render(reading)?);
+
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Any ISO 3166 alpha-2 code, those without channel rules of their own are warned about later.
+fn known_country(country: &str) -> Result<(), String> {
+    let country = country.trim();
+
+    match country.len() == 2 && country.chars().all(|c| c.is_ascii_alphabetic()) {
+        true => Ok(()),
+        false => Err(format!("Not a two letter country code: {:?}.", country)),
+    }
+}
+
+fn signal_change(threshold: &str) -> Result<(), String> {
+    match threshold.parse::<f32>() {
+        Ok(db) if db.is_finite() && db >= 0.0 => Ok(()),
+        _ => Err(format!("Not a signal change in dB: {:?}.", threshold)),
+    }
+}
+
+/// Loads a saved measure, or downloads a reading from the server when there's no such file and
+/// it doesn't look like a path either, so a mistyped file name isn't sent to the server.
+fn load_or_fetch(file: String, server: &str) -> anyhow::Result<Measure> {
+    let path = file.contains('/') || file.ends_with(".json");
+
+    match path || std::path::Path::new(&file).exists() {
+        true => Measure::load(file),
+        false => fetch(&file, server).map(Measure::from),
+    }
+}
+
+/// Outputs how each network was heard around the site.
+fn output_observations(site: &Site) {
+    println!(
+        "Site survey of {} from {} measures.",
+        site.local, site.points
+    );
+
+    site.observations.iter().for_each(|observation| {
+        println!(
+            "Wifi network with SSID and MAC: {}, {}.\n\tChannel: {}.\n\tSeen at {} of {} points, signal max {:.0} dBm, mean {:.1} dBm, 10th percentile {:.0} dBm.",
+            observation.wifi.ssid,
+            observation.wifi.mac,
+            observation.wifi.channel,
+            observation.seen(),
+            site.points,
+            observation.max(),
+            observation.mean(),
+            observation.percentile(10.0)
+        )
+    });
+}
+
+/// Reports something odd that doesn't stop the command.
+fn warn(message: impl std::fmt::Display) {
+    eprintln!("Warning: {}", message);
+}
+
+fn run(command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::Scan {
+            place,
+            save,
+            source,
+            mut plan,
+            output,
+        } => {
+            // synthetic networks come from the seed of the suggestions, so both can be repeated
+            let seed = plan.seed();
+            plan.seed = Some(seed);
+
+            let measure = measure(place, source.source(seed).as_ref())?;
+            measure.warnings.iter().for_each(warn);
+
+            if let Some(save) = save {
+                measure.save(save)?;
+            }
+
+            let reading = plan.analyse(measure)?;
+
+            output.output(&reading)
+        }
+        Command::Analyze { file, plan, output } => {
+            let reading = plan.analyse(Measure::load(file)?)?;
+
+            output.output(&reading)
+        }
+        Command::Diff {
+            before,
+            after,
+            threshold,
+            json,
+            server,
+        } => {
+            let before = load_or_fetch(before, &server)?;
+            let after = load_or_fetch(after, &server)?;
+            let diff = Diff::new(&before.networks, &after.networks, threshold);
+
+            match json {
+                true => println!("{}", serde_json::to_string_pretty(&diff)?),
+                false => println!("{}", diff),
+            }
+
+            Ok(())
+        }
+        Command::Upload { file, server } => {
+            let reading = Reading::deserialize(file)?;
+
+            println!("{}", upload(&reading, &server)?);
+
+            Ok(())
+        }
+        Command::Daemon {
+            place,
+            server,
+            plan,
+        } => {
+            let planner = plan.planner.planner();
+            daemon_service(
+                place,
+                &server,
+                plan.country(),
+                &plan.fleet()?,
+                planner.as_ref(),
+                plan.seed,
+            )
+        }
+        Command::Survey {
+            dir,
+            place,
+            plan,
+            output,
+        } => {
+            let site = Site::load(dir, place.as_deref())?;
+            site.warnings.iter().for_each(warn);
+
+            // the other formats are meant to be read by other tools
+            if output.format == Format::Text {
+                output_observations(&site);
+            }
+
+            let planner = plan.planner.planner();
+            let reading = site.plan(
+                plan.country(),
+                &plan.fleet()?,
+                planner.as_ref(),
+                plan.seed(),
+            )?;
+
+            output.output(&reading)
+        }
+        Command::Benchmark {
+            rounds,
+            networks,
+            seed,
+        } => benchmark(rounds, networks, seed.unwrap_or_else(rand::random)),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    if let Err(err) = run(cli.command) {
+        eprintln!("Error: {:?}", err);
+
+        std::process::exit(match err.chain().any(|cause| cause.is::<ServerError>()) {
+            true => EXIT_SERVER,
+            false => EXIT_FAILURE,
+        });
+    }
 }
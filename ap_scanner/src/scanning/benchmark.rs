@@ -0,0 +1,153 @@
+// Benchmark of the channel planners on synthetic environments.
+//
+// Every round draws a random site, networks spread over a few points and heard weaker away from
+// their own, and has each planner plan its 2.4 GHz band and its 5 and 6 GHz bands at 80 MHz
+// under the world rules. Every network may be moved. Plans are scored the same way whatever the
+// planner. Everything random is drawn from a single seed, so a run can be repeated.
+use super::planner::{Problem, Score, Strategy, NOISE_FLOOR_DBM};
+use super::regulatory::Domain;
+use super::source::{ScanSource, SyntheticSource};
+use super::wifi::Wifi;
+use anyhow::Result;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::time::{Duration, Instant};
+
+// rooms or floors of a synthetic site
+const POINTS: usize = 4;
+
+// weaker than this a network isn't heard at a point
+const SENSITIVITY_DBM: f32 = -92.0;
+
+/// How one planner did on one band, summed over the rounds.
+#[derive(Default)]
+struct Tally {
+    rounds: usize,
+    total: f32,
+    worst: f32,
+    // over the plan of today
+    gain: f32,
+    time: Duration,
+}
+
+impl Tally {
+    fn add(&mut self, score: Score, gain: f32, time: Duration) {
+        // a plan nothing interferes with is as good as it gets, down at the noise floor
+        self.rounds += 1;
+        self.total += score.total.unwrap_or(NOISE_FLOOR_DBM);
+        self.worst += score.worst.unwrap_or(NOISE_FLOOR_DBM);
+        self.gain += gain;
+        self.time += time;
+    }
+
+    fn output(&self, name: &str) {
+        match self.rounds {
+            0 => println!("\t{}: no rounds", name),
+            rounds => println!(
+                "\t{}: total {:.1} dBm, worst network {:.1} dBm, {:.1} dB better than today, {:.1} ms",
+                name,
+                self.total / rounds as f32,
+                self.worst / rounds as f32,
+                self.gain / rounds as f32,
+                self.time.as_secs_f32() * 1000.0 / rounds as f32
+            ),
+        }
+    }
+}
+
+/// The signal of every network at each point of a random site.
+/// Each network is heard as scanned at one point and weaker at the others.
+fn spread(networks: &[Wifi], rng: &mut impl Rng) -> Vec<Vec<Option<f32>>> {
+    networks
+        .iter()
+        .map(|wifi| {
+            let home = rng.gen_range(0..POINTS);
+
+            (0..POINTS)
+                .map(|point| match point == home {
+                    true => Some(wifi.signal),
+                    false => Some(wifi.signal - rng.gen_range(5.0..30.0))
+                        .filter(|signal| *signal >= SENSITIVITY_DBM),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Runs every planner over `rounds` synthetic sites of `count` networks drawn from `seed` and
+/// prints how they did on average.
+pub fn benchmark(rounds: usize, count: usize, seed: u64) -> Result<()> {
+    let domain = Domain::resolve(None);
+    let candidates_2g = domain.candidates_2g();
+    let centers_80 = domain.blocks_5g(80, true);
+    let centers_6g = domain.blocks_6g(80);
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    // by band, today's plan first and then every planner's
+    let mut tallies = [(); 3].map(|_| {
+        (0..=Strategy::ALL.len())
+            .map(|_| Tally::default())
+            .collect::<Vec<_>>()
+    });
+
+    for _ in 0..rounds {
+        let networks = SyntheticSource::new(count, rng.gen()).scan()?.networks;
+        let heard = spread(&networks, &mut rng);
+
+        let (left, rest): (Vec<_>, Vec<_>) = networks
+            .into_iter()
+            .zip(heard)
+            .partition(|(wifi, _)| wifi.is_2_4g());
+        let (right, rest): (Vec<_>, Vec<_>) = rest.into_iter().partition(|(wifi, _)| wifi.is_5g());
+        let (left, heard_2g): (Vec<_>, Vec<_>) = left.into_iter().unzip();
+        let (right, heard_5g): (Vec<_>, Vec<_>) = right.into_iter().unzip();
+        let (six, heard_6g): (Vec<_>, Vec<_>) =
+            rest.into_iter().filter(|(wifi, _)| wifi.is_6g()).unzip();
+
+        let movable_2g = vec![true; left.len()];
+        let movable_5g = vec![true; right.len()];
+        let movable_6g = vec![true; six.len()];
+
+        let problems = [
+            Problem::new_2g(&left, &heard_2g, &movable_2g, &candidates_2g),
+            Problem::new_5g(&right, &heard_5g, &movable_5g, 80, &centers_80),
+            Problem::new_5g(&six, &heard_6g, &movable_6g, 80, &centers_6g),
+        ];
+
+        for (problem, tallies) in problems.iter().zip(tallies.iter_mut()) {
+            let today = problem.objective_today();
+
+            tallies[0].add(problem.score_today(), 0.0, Duration::default());
+
+            for (strategy, tally) in Strategy::ALL.iter().zip(&mut tallies[1..]) {
+                let start = Instant::now();
+                let plan = strategy.planner().plan(problem, rng.gen());
+
+                tally.add(
+                    problem.score(&plan),
+                    today - problem.objective(&plan),
+                    start.elapsed(),
+                );
+            }
+        }
+    }
+
+    println!(
+        "Planner benchmark, {} rounds of {} networks spread over {} points, seed {}.",
+        rounds, count, POINTS, seed
+    );
+
+    let bands = ["Wifi 2.4GHz.", "Wifi 5GHz at 80MHz.", "Wifi 6GHz at 80MHz."];
+
+    for (band, tallies) in bands.iter().zip(&tallies) {
+        println!("{}", band);
+
+        tallies[0].output("today");
+
+        for (strategy, tally) in Strategy::ALL.iter().zip(&tallies[1..]) {
+            tally.output(&strategy.to_string());
+        }
+    }
+
+    Ok(())
+}
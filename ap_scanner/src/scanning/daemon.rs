@@ -5,32 +5,98 @@
 
 //  - Periodically check if the server has new advice
 //  - Server will periodically send new advice
-use super::reading::Reading;
+use super::{fleet::Fleet, planner::ChannelPlanner, reading::Reading, scanner::Scanner, source};
+use std::fmt::Display;
+
+/// The server couldn't be reached or turned the request down.
+#[derive(Debug)]
+pub struct ServerError(String);
+
+impl Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Server request failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for ServerError {}
+
+/// Failures in a row after which the daemon gives up.
+const MAX_FAILURES: usize = 3;
+
+/// Takes a reading every 5 minutes and uploads it, each with a seed of its own unless `seed` is
+/// given. Gives up with the last error once [`MAX_FAILURES`] readings in a row couldn't be taken
+/// or uploaded.
+pub fn daemon_service(
+    local: String,
+    server: &str,
+    country: Option<String>,
+    fleet: &Fleet,
+    planner: &dyn ChannelPlanner,
+    seed: Option<u64>,
+) -> anyhow::Result<()> {
+    let mut failures = 0;
 
-pub fn daemon_service(local: String) {
     loop {
         std::thread::sleep(std::time::Duration::from_secs(300));
 
         // upload readings to server
-        match Reading::new(local.to_string()) {
-            Ok(reading) => upload_reading(reading),
-            Err(_) => continue,
+        match source::measure(local.to_string(), &Scanner)
+            .and_then(|measure| {
+                measure.analyse(
+                    country.clone(),
+                    fleet,
+                    planner,
+                    seed.unwrap_or_else(rand::random),
+                )
+            })
+            .and_then(|reading| upload(&reading, server))
+        {
+            Ok(_) => {
+                failures = 0;
+                println!("Uploaded reading");
+            }
+            Err(err) => {
+                failures += 1;
+                if failures == MAX_FAILURES {
+                    return Err(err.context(format!(
+                        "Gave up after {} failed readings in a row",
+                        MAX_FAILURES
+                    )));
+                }
+                eprintln!("Failed to upload reading: {:#}", err);
+            }
         }
     }
 }
 
-#[inline]
-fn upload_reading(readings: Reading) {
-    // use reqwest to upload reading to server
-    // use serde_json to serialize reading
-    let body = serde_json::to_string(&readings).unwrap();
+/// Uploads a reading to the server, returns where the server keeps it.
+pub fn upload(reading: &Reading, server: &str) -> anyhow::Result<String> {
+    let body = serde_json::to_string(reading)?;
     let blocking_client = reqwest::blocking::Client::new();
-    match blocking_client
-        .post("http://0.0.0.0:9999/")
+
+    let response = blocking_client
+        .post(server)
         .body(body)
         .send()
-    {
-        Ok(_resp) => println!("Uploaded reading"),
-        Err(_) => println!("Failed to upload reading"),
-    };
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| ServerError(err.to_string()))?;
+
+    Ok(response
+        .text()
+        .map_err(|err| ServerError(err.to_string()))?)
+}
+
+/// Downloads the reading the server keeps under `id`.
+pub fn fetch(id: &str, server: &str) -> anyhow::Result<Reading> {
+    let blocking_client = reqwest::blocking::Client::new();
+
+    let response = blocking_client
+        .get(format!("{}/{}", server.trim_end_matches('/'), id))
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| ServerError(err.to_string()))?;
+
+    Ok(response
+        .json()
+        .map_err(|err| ServerError(err.to_string()))?)
 }
@@ -0,0 +1,325 @@
+// 802.11 information elements, as found in beacons and probe responses.
+//
+// Each element is laid out as a one byte id, a one byte length and `length` bytes of body.
+// Newer elements live behind the extension id, with their real id as the first body byte.
+use super::{
+    bss::{BssLoad, Country, Environment, PowerLimit},
+    security::{Akm, Cipher, Suites},
+    wifi::Width,
+};
+
+pub const SSID: u8 = 0;
+pub const DS_PARAMETER_SET: u8 = 3;
+pub const COUNTRY: u8 = 7;
+pub const BSS_LOAD: u8 = 11;
+pub const RSN: u8 = 48;
+pub const HT_OPERATION: u8 = 61;
+pub const VHT_OPERATION: u8 = 192;
+pub const VENDOR_SPECIFIC: u8 = 221;
+pub const EXTENSION: u8 = 255;
+
+// WPA and WPS predate RSN and live in vendor elements under Microsoft's OUI
+const MICROSOFT_OUI: [u8; 3] = [0x00, 0x50, 0xf2];
+const MICROSOFT_WPA: u8 = 1;
+const MICROSOFT_WPS: u8 = 4;
+
+pub const EXT_HE_OPERATION: u8 = 36;
+pub const EXT_EHT_OPERATION: u8 = 106;
+
+/// Iterates over the `(id, body)` pairs of a raw information element blob.
+/// A truncated trailing element ends the iteration.
+#[derive(Clone, Copy)]
+pub struct InformationElements<'a>(&'a [u8]);
+
+impl<'a> InformationElements<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self(data)
+    }
+
+    /// Body of the first element with the given id.
+    pub fn get(mut self, id: u8) -> Option<&'a [u8]> {
+        self.find(|(elem, _)| *elem == id).map(|(_, body)| body)
+    }
+
+    pub fn ssid(self) -> Option<String> {
+        self.get(SSID)
+            .map(|body| String::from_utf8_lossy(body).into_owned())
+    }
+
+    /// Channel announced in the DS Parameter Set, only sent on 2.4 GHz.
+    pub fn ds_channel(self) -> Option<u8> {
+        self.get(DS_PARAMETER_SET)
+            .and_then(|body| body.first().copied())
+    }
+
+    pub fn bss_load(self) -> Option<BssLoad> {
+        match self.get(BSS_LOAD)? {
+            [sta_0, sta_1, utilization, cap_0, cap_1, ..] => Some(BssLoad {
+                station_count: u16::from_le_bytes([*sta_0, *sta_1]),
+                channel_utilization: *utilization,
+                admission_capacity: u16::from_le_bytes([*cap_0, *cap_1]),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn country(self) -> Option<Country> {
+        let body = self.get(COUNTRY)?;
+        let code = body.get(..2)?;
+
+        Some(Country {
+            code: String::from_utf8_lossy(code).into_owned(),
+            environment: Environment::from_byte(*body.get(2)?),
+            // first channels above 200 start operating class triplets, which carry no limits
+            power_limits: body[3..]
+                .as_chunks::<3>()
+                .0
+                .iter()
+                .filter(|[first, ..]| *first <= 200)
+                .map(|[first, count, power]| PowerLimit::from_triplet(*first, *count, *power as i8))
+                .collect(),
+        })
+    }
+
+    pub fn ht_operation(self) -> Option<HtOperation> {
+        self.get(HT_OPERATION).and_then(HtOperation::parse)
+    }
+
+    /// Body of a vendor element, without the OUI and type.
+    pub fn vendor(mut self, oui: [u8; 3], kind: u8) -> Option<&'a [u8]> {
+        self.find(|(id, body)| {
+            *id == VENDOR_SPECIFIC && body.get(..4) == Some(&[oui[0], oui[1], oui[2], kind])
+        })
+        .map(|(_, body)| &body[4..])
+    }
+
+    pub fn rsn(self) -> Option<Suites> {
+        self.get(RSN).map(parse_suites)
+    }
+
+    pub fn wpa(self) -> Option<Suites> {
+        self.vendor(MICROSOFT_OUI, MICROSOFT_WPA).map(parse_suites)
+    }
+
+    pub fn wps(self) -> bool {
+        self.vendor(MICROSOFT_OUI, MICROSOFT_WPS).is_some()
+    }
+
+    /// Body of an extension element, without the extension id.
+    pub fn extension(self, ext: u8) -> Option<&'a [u8]> {
+        self.filter(|(id, _)| *id == EXTENSION)
+            .find_map(|(_, body)| body.split_first().filter(|(id, _)| **id == ext))
+            .map(|(_, body)| body)
+    }
+
+    /// The block set by VHT Operation, `None` when it defers to HT Operation.
+    pub fn vht_operation(self) -> Option<ChannelBlock> {
+        match self.get(VHT_OPERATION)? {
+            [width, seg0, seg1, ..] => ChannelBlock::vht(*width, *seg0, *seg1),
+            _ => None,
+        }
+    }
+
+    /// The primary channel and block of a 6 GHz HE BSS.
+    pub fn he_6ghz_operation(self) -> Option<(u8, ChannelBlock)> {
+        let body = self.extension(EXT_HE_OPERATION)?;
+        let params =
+            *body.first()? as u32 | (*body.get(1)? as u32) << 8 | (*body.get(2)? as u32) << 16;
+
+        // parameters, BSS color and basic HE-MCS come first, then the optional fields
+        let mut offset = 6;
+
+        if params & (1 << 14) != 0 {
+            // VHT Operation Information
+            offset += 3;
+        }
+
+        if params & (1 << 15) != 0 {
+            // Co-Hosted BSS
+            offset += 1;
+        }
+
+        if params & (1 << 17) == 0 {
+            return None;
+        }
+
+        match body.get(offset..offset + 4)? {
+            [primary, control, ccfs0, ccfs1] => {
+                ChannelBlock::he_eht(control & 0b11, *ccfs0, *ccfs1).map(|block| (*primary, block))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn eht_operation(self) -> Option<ChannelBlock> {
+        let body = self.extension(EXT_EHT_OPERATION)?;
+
+        // the operation information is only there when it differs from HE
+        if body.first()? & 1 == 0 {
+            return None;
+        }
+
+        match body.get(5..8)? {
+            [control, ccfs0, ccfs1] => ChannelBlock::he_eht(control & 0b111, *ccfs0, *ccfs1),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Iterator for InformationElements<'a> {
+    type Item = (u8, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&id, rest) = self.0.split_first()?;
+        let (&len, rest) = rest.split_first()?;
+
+        if rest.len() < len as usize {
+            self.0 = &[];
+            return None;
+        }
+
+        let (body, rest) = rest.split_at(len as usize);
+        self.0 = rest;
+
+        Some((id, body))
+    }
+}
+
+/// Parses the body of an RSN element, or of a WPA element past its OUI and type.
+/// Everything after the version is optional, parsing stops at the first missing field.
+fn parse_suites(body: &[u8]) -> Suites {
+    let mut suites = Suites::default();
+    let mut rest = body.get(2..).unwrap_or_default();
+
+    fn suite(rest: &mut &[u8]) -> Option<([u8; 3], u8)> {
+        match rest.get(..4)? {
+            [a, b, c, kind] => {
+                let suite = ([*a, *b, *c], *kind);
+                *rest = &rest[4..];
+                Some(suite)
+            }
+            _ => None,
+        }
+    }
+
+    fn list(rest: &mut &[u8]) -> Vec<([u8; 3], u8)> {
+        let count = match rest.get(..2) {
+            Some(count) => u16::from_le_bytes([count[0], count[1]]),
+            None => return vec![],
+        };
+        *rest = &rest[2..];
+
+        (0..count).map_while(|_| suite(rest)).collect()
+    }
+
+    suites.group_cipher = suite(&mut rest).map(|(oui, kind)| Cipher::from_suite(oui, kind));
+    suites.pairwise_ciphers = list(&mut rest)
+        .into_iter()
+        .map(|(oui, kind)| Cipher::from_suite(oui, kind))
+        .collect();
+    suites.akm_suites = list(&mut rest)
+        .into_iter()
+        .map(|(oui, kind)| Akm::from_suite(oui, kind))
+        .collect();
+
+    if let Some(caps) = rest.get(..2) {
+        let caps = u16::from_le_bytes([caps[0], caps[1]]);
+        suites.pmf_required = caps & (1 << 6) != 0;
+        suites.pmf_capable = caps & (1 << 7) != 0;
+    }
+
+    suites
+}
+
+/// The fields of the HT Operation element the scanner cares about.
+#[derive(Debug)]
+pub struct HtOperation {
+    pub primary_channel: u8,
+    // 0: no secondary channel, 1: above the primary, 3: below the primary
+    pub secondary_offset: u8,
+    // whether the AP allows 40 MHz transmissions
+    pub any_width: bool,
+}
+
+impl HtOperation {
+    fn parse(body: &[u8]) -> Option<Self> {
+        match body {
+            [primary_channel, info, ..] => Some(Self {
+                primary_channel: *primary_channel,
+                secondary_offset: info & 0b11,
+                any_width: info & 0b100 != 0,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Where a BSS transmits: its operating width and the center channel of the block.
+/// 80+80 MHz BSSes have a second center, one per segment.
+#[derive(Debug)]
+pub struct ChannelBlock {
+    pub width: Width,
+    pub center: u8,
+    pub center_2: Option<u8>,
+}
+
+impl ChannelBlock {
+    pub fn new(width: Width, center: u8) -> Self {
+        Self {
+            width,
+            center,
+            center_2: None,
+        }
+    }
+
+    /// Resolves the 80, 160 and 80+80 MHz cases shared by VHT, HE and EHT.
+    /// A second segment 8 channels away from the first is the center of a contiguous 160 MHz block.
+    pub fn from_segments(seg0: u8, seg1: u8) -> Self {
+        match seg1 {
+            0 => Self::new(Width::MHz80, seg0),
+            _ if seg0.abs_diff(seg1) == 8 => Self::new(Width::MHz160, seg1),
+            _ => Self {
+                width: Width::MHz160,
+                center: seg0,
+                center_2: Some(seg1),
+            },
+        }
+    }
+
+    /// From the channel width field of VHT Operation.
+    /// 0 means 20 or 40 MHz, as set by HT Operation.
+    pub fn vht(width: u8, seg0: u8, seg1: u8) -> Option<Self> {
+        match width {
+            1 => Some(Self::from_segments(seg0, seg1)),
+            // deprecated 160 and 80+80 MHz signalling
+            2 => Some(Self::new(Width::MHz160, seg0)),
+            3 => Some(Self {
+                width: Width::MHz160,
+                center: seg0,
+                center_2: Some(seg1),
+            }),
+            _ => None,
+        }
+    }
+
+    /// From the channel width field of the HE 6 GHz or EHT operation information.
+    pub fn he_eht(width: u8, ccfs0: u8, ccfs1: u8) -> Option<Self> {
+        match width {
+            0 => Some(Self::new(Width::MHz20, ccfs0)),
+            1 => Some(Self::new(Width::MHz40, ccfs0)),
+            2 => Some(Self::new(Width::MHz80, ccfs0)),
+            3 => Some(Self::from_segments(ccfs0, ccfs1)),
+            4 => Some(Self::new(Width::MHz320, ccfs1)),
+            _ => None,
+        }
+    }
+
+    /// The 20 or 40 MHz block announced by HT Operation.
+    pub fn ht(ht: &HtOperation) -> Self {
+        match ht.secondary_offset {
+            1 if ht.any_width => Self::new(Width::MHz40, ht.primary_channel + 2),
+            3 if ht.any_width => Self::new(Width::MHz40, ht.primary_channel.saturating_sub(2)),
+            _ => Self::new(Width::MHz20, ht.primary_channel),
+        }
+    }
+}
@@ -0,0 +1,587 @@
+// Parser for the text printed by `iw dev <iface> scan`.
+//
+// Every BSS starts with an unindented `BSS <mac>(on <iface>)` line, followed by tab indented
+// `key: value` lines. Deeper indented lines (often prefixed with ` * `) belong to the line above
+// them, e.g. the fields of "HT operation". Fields are looked up by name, so drivers that print
+// extra lines, leave some out or order them differently still parse.
+use super::{
+    bss::{BssLoad, Capability, Country, Environment, PowerLimit},
+    ie::{ChannelBlock, HtOperation},
+    security::{Akm, Cipher, Security, Suites},
+    wifi::{Frequency, Width, Wifi},
+};
+use anyhow::{anyhow, Context, Result};
+use std::fmt::Display;
+
+/// A `key: value` line and the lines indented below it.
+#[derive(Debug)]
+pub struct Section<'a> {
+    pub key: &'a str,
+    pub value: &'a str,
+    pub children: Vec<Section<'a>>,
+}
+
+impl<'a> Section<'a> {
+    /// First child section with the given key.
+    pub fn section(&self, key: &str) -> Option<&Section<'a>> {
+        self.children.iter().find(|sec| sec.key == key)
+    }
+
+    /// Value of the first child section with the given key.
+    pub fn value(&self, key: &str) -> Option<&'a str> {
+        self.section(key).map(|sec| sec.value)
+    }
+
+    /// First section with the given key at any depth below this one.
+    pub fn find(&self, key: &str) -> Option<&Section<'a>> {
+        self.section(key)
+            .or_else(|| self.children.iter().find_map(|sec| sec.find(key)))
+    }
+}
+
+/// The sections of a single BSS.
+#[derive(Debug)]
+pub struct BssBlock<'a> {
+    pub mac: &'a str,
+    pub root: Section<'a>,
+}
+
+impl<'a> BssBlock<'a> {
+    /// Parses one block, starting at its `BSS` line (the `BSS ` prefix itself is optional).
+    pub fn parse(block: &'a str) -> Result<Self> {
+        let mut lines = block.lines();
+
+        let mac = lines
+            .next()
+            .map(|line| line.trim_start_matches("BSS "))
+            .and_then(|line| line.split(|c: char| c == '(' || c.is_whitespace()).next())
+            .filter(|mac| !mac.is_empty())
+            .with_context(|| anyhow!("Error parsing BSS."))?;
+
+        let entries = lines.flat_map(tokenize).collect::<Vec<_>>();
+
+        Ok(Self {
+            mac,
+            root: Section {
+                key: "",
+                value: "",
+                children: build(&entries),
+            },
+        })
+    }
+
+    pub fn section(&self, key: &str) -> Option<&Section<'a>> {
+        self.root.section(key)
+    }
+
+    pub fn value(&self, key: &str) -> Option<&'a str> {
+        self.root.value(key)
+    }
+}
+
+/// Something odd found while parsing, reported instead of silently dropping data.
+#[derive(Debug)]
+pub struct Warning {
+    pub mac: String,
+    pub message: String,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BSS {}: {}", self.mac, self.message)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedScan {
+    pub networks: Vec<Wifi>,
+    pub warnings: Vec<Warning>,
+}
+
+/// Networks from a source with nothing to warn about.
+impl From<Vec<Wifi>> for ParsedScan {
+    fn from(networks: Vec<Wifi>) -> Self {
+        Self {
+            networks,
+            warnings: vec![],
+        }
+    }
+}
+
+/// Parses a whole scan. Networks that can't be used end up in the warnings, with the reason.
+pub fn parse_scan(output: &str) -> ParsedScan {
+    let mut parsed = ParsedScan::default();
+
+    for block in split_blocks(output) {
+        let (mac, messages) = match BssBlock::parse(block) {
+            Ok(bss) => match parse_bss(&bss) {
+                Ok((wifi, messages)) => {
+                    parsed.networks.push(wifi);
+                    (bss.mac, messages)
+                }
+                Err(err) => (bss.mac, vec![format!("dropped, {}", err)]),
+            },
+            Err(err) => ("?", vec![format!("dropped, {}", err)]),
+        };
+
+        parsed
+            .warnings
+            .extend(messages.into_iter().map(|message| Warning {
+                mac: mac.to_string(),
+                message,
+            }));
+    }
+
+    parsed
+}
+
+/// Builds a `Wifi` out of a block.
+/// Returns the non fatal issues alongside it, errors only when a mandatory field is missing.
+pub fn parse_bss(bss: &BssBlock) -> Result<(Wifi, Vec<String>)> {
+    let mut warnings = vec![];
+
+    let frequency: Frequency = bss
+        .value("freq")
+        .with_context(|| anyhow!("Error parsing frequency."))?
+        .parse()?;
+
+    let signal = bss
+        .value("signal")
+        .and_then(|sig| sig.split(' ').next())
+        .and_then(|sig| sig.parse().ok())
+        .with_context(|| anyhow!("Error parsing signal."))?;
+
+    let ssid = match bss.value("SSID") {
+        Some(ssid) => unescape(ssid),
+        None => {
+            warnings.push("no SSID element".to_string());
+            String::new()
+        }
+    };
+
+    let ht = bss.section("HT operation");
+    let he_6ghz = bss
+        .section("HE Operation")
+        .and_then(|he| he.find("6 GHz Operation Information"));
+
+    // legacy 802.11a/b/g APs have no HT operation element, fall back to the
+    // DS Parameter Set and then to the frequency itself
+    let channel = match ht
+        .and_then(|ht| ht.value("primary channel"))
+        .or_else(|| he_6ghz.and_then(|he| he.value("Primary Channel")))
+    {
+        Some(channel) => channel.parse()?,
+        None => bss
+            .value("DS Parameter set")
+            .and_then(|ds| ds.trim_start_matches("channel").trim().parse().ok())
+            .or_else(|| frequency.channel())
+            .with_context(|| anyhow!("Couldn't parse channel."))?,
+    };
+
+    let ht_block = match ht {
+        // non-HT stations only ever use 20 MHz
+        None => None,
+        Some(ht) => {
+            // "any" means the AP may use the secondary channel as well
+            let any_width = match ht.value("STA channel width") {
+                Some("any") => true,
+                Some(width) => width.parse::<Width>()? != Width::MHz20,
+                None => {
+                    warnings.push("no STA channel width, assuming 20 MHz".to_string());
+                    false
+                }
+            };
+
+            let secondary_offset = match ht.value("secondary channel offset") {
+                Some("above") => 1,
+                Some("below") => 3,
+                _ => 0,
+            };
+
+            Some(ChannelBlock::ht(&HtOperation {
+                primary_channel: channel,
+                secondary_offset,
+                any_width,
+            }))
+        }
+    };
+
+    let capability = bss.value("capability").map(Capability::from_iw);
+
+    // the newest generation of operation element describes the real block
+    let block = bss
+        .section("EHT Operation")
+        .and_then(he_eht_block)
+        .or_else(|| he_6ghz.and_then(he_eht_block))
+        .or_else(|| bss.section("VHT operation").and_then(vht_block))
+        .or(ht_block)
+        .unwrap_or_else(|| ChannelBlock::new(Width::MHz20, channel));
+
+    Ok((
+        Wifi {
+            ssid,
+            mac: bss.mac.to_string(),
+            channel,
+            signal,
+            frequency,
+            width: block.width,
+            center_channel: Some(block.center),
+            center_channel_2: block.center_2,
+            security: Some(Security::new(
+                bss.section("RSN").map(suites),
+                bss.section("WPA").map(suites),
+                capability
+                    .map(|caps| caps.has(Capability::PRIVACY))
+                    .unwrap_or_default(),
+                bss.section("WPS").is_some(),
+            )),
+            bss_load: bss.section("BSS Load").and_then(bss_load),
+            country: bss.section("Country").and_then(country),
+            beacon_interval: bss
+                .value("beacon interval")
+                .and_then(|interval| interval.trim_end_matches("TUs").trim().parse().ok()),
+            capability,
+        },
+        warnings,
+    ))
+}
+
+/// The suites of an "RSN" or "WPA" section.
+fn suites(section: &Section) -> Suites {
+    let ciphers = |list: &str| list.split_whitespace().map(Cipher::from_iw).collect();
+    let caps = section.value("Capabilities").unwrap_or_default();
+
+    Suites {
+        group_cipher: section.value("Group cipher").map(Cipher::from_iw),
+        pairwise_ciphers: section
+            .value("Pairwise ciphers")
+            .map(ciphers)
+            .unwrap_or_default(),
+        akm_suites: section
+            .value("Authentication suites")
+            .map(Akm::parse_iw_list)
+            .unwrap_or_default(),
+        pmf_required: caps.contains("MFP-required"),
+        pmf_capable: caps.contains("MFP-capable"),
+    }
+}
+
+fn bss_load(load: &Section) -> Option<BssLoad> {
+    // e.g. "48/255" and "0 [*32us]"
+    let leading = |key: &str| {
+        load.value(key)
+            .and_then(|value| value.split(|c: char| !c.is_ascii_digit()).next())
+    };
+
+    Some(BssLoad {
+        station_count: leading("station count")?.parse().ok()?,
+        channel_utilization: leading("channel utilisation")?.parse().ok()?,
+        admission_capacity: leading("available admission capacity")?.parse().ok()?,
+    })
+}
+
+/// From e.g. "PT\tEnvironment: Indoor/Outdoor" and its "Channels [1 - 13] @ 20 dBm" lines.
+fn country(country: &Section) -> Option<Country> {
+    let (code, environment) = match country.value.split_once("Environment:") {
+        Some((code, environment)) => (code, Environment::from_iw(environment.trim())),
+        None => (country.value, Environment::Any),
+    };
+
+    let power_limits = country
+        .children
+        .iter()
+        .filter_map(|line| {
+            let (channels, power) = line.key.strip_prefix("Channels [")?.split_once("] @")?;
+            let (first, last) = channels.split_once('-')?;
+
+            Some(PowerLimit {
+                first_channel: first.trim().parse().ok()?,
+                last_channel: last.trim().parse().ok()?,
+                max_power_dbm: power.trim().trim_end_matches("dBm").trim().parse().ok()?,
+            })
+        })
+        .collect();
+
+    Some(Country {
+        code: code.trim().to_string(),
+        environment,
+        power_limits,
+    })
+}
+
+fn vht_block(vht: &Section) -> Option<ChannelBlock> {
+    // e.g. "1 (80 MHz)"
+    let width = vht
+        .value("channel width")?
+        .split(' ')
+        .next()?
+        .parse()
+        .ok()?;
+    let seg0 = vht.value("center freq segment 1")?.parse().ok()?;
+    let seg1 = vht.value("center freq segment 2")?.parse().ok()?;
+
+    ChannelBlock::vht(width, seg0, seg1)
+}
+
+fn he_eht_block(info: &Section) -> Option<ChannelBlock> {
+    let width = info.find("Channel Width")?.value;
+    // 80+80 shares its code with 160 MHz
+    let width = [("320", 4), ("160", 3), ("80", 2), ("40", 1), ("20", 0)]
+        .iter()
+        .find(|(mhz, _)| width.contains(mhz))
+        .map(|(_, code)| *code)?;
+
+    let segment = |idx: u8| {
+        info.find(&format!("Channel Center Frequency Segment {}", idx))
+            .and_then(|sec| sec.value.parse().ok())
+    };
+
+    ChannelBlock::he_eht(width, segment(0)?, segment(1).unwrap_or(0))
+}
+
+/// Splits a scan into the text of each BSS, `BSS` line included.
+fn split_blocks(output: &str) -> Vec<&str> {
+    let starts = output
+        .match_indices("BSS ")
+        .map(|(idx, _)| idx)
+        .filter(|idx| *idx == 0 || output.as_bytes()[idx - 1] == b'\n')
+        .collect::<Vec<_>>();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, start)| &output[*start..*starts.get(i + 1).unwrap_or(&output.len())])
+        .collect()
+}
+
+/// Turns a line into `(depth, key, value)` entries.
+/// `RSN:\t * Version: 1` style lines carry their first child inline and yield two entries.
+fn tokenize(line: &str) -> Vec<(usize, &str, &str)> {
+    let depth = line.chars().take_while(|c| *c == '\t').count();
+    let line = line.trim();
+
+    if line.is_empty() {
+        return vec![];
+    }
+
+    let (key, value) = split_key(line);
+    let inline = value.trim_start_matches('*').trim_start();
+
+    if value.starts_with('*') && !inline.is_empty() {
+        let (child_key, child_value) = split_key(inline);
+        vec![(depth, key, ""), (depth + 1, child_key, child_value)]
+    } else {
+        vec![(depth, key, value)]
+    }
+}
+
+fn split_key(line: &str) -> (&str, &str) {
+    let line = line.trim_start_matches("* ");
+
+    match line.split_once(':') {
+        Some((key, value)) => (key.trim(), value.trim()),
+        None => (line, ""),
+    }
+}
+
+fn build<'a>(entries: &[(usize, &'a str, &'a str)]) -> Vec<Section<'a>> {
+    let mut out = vec![];
+    let mut idx = 0;
+
+    while let Some(&(depth, key, value)) = entries.get(idx) {
+        let end = entries[idx + 1..]
+            .iter()
+            .position(|(d, _, _)| *d <= depth)
+            .map(|pos| idx + 1 + pos)
+            .unwrap_or(entries.len());
+
+        out.push(Section {
+            key,
+            value,
+            children: build(&entries[idx + 1..end]),
+        });
+
+        idx = end;
+    }
+
+    out
+}
+
+/// Undoes the `\xNN` escaping `iw` applies to unprintable SSID bytes.
+fn unescape(ssid: &str) -> String {
+    let mut bytes = vec![];
+    let mut rest = ssid.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = tail
+            .strip_prefix(b"x")
+            .and_then(|hex| hex.get(..2))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (byte, escaped) {
+            (b'\\', Some(val)) => {
+                bytes.push(val);
+                rest = &tail[3..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    // hidden networks sometimes advertise a run of NUL bytes instead of an empty SSID
+    if bytes.iter().all(|b| *b == 0) {
+        return String::new();
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanning::security::AuthType;
+
+    fn parse(fixture: &str) -> ParsedScan {
+        let parsed = parse_scan(fixture);
+        let warnings = parsed
+            .warnings
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        assert!(warnings.is_empty(), "unexpected warnings {:?}", warnings);
+        parsed
+    }
+
+    fn auth(wifi: &Wifi) -> AuthType {
+        wifi.security.as_ref().unwrap().auth
+    }
+
+    #[test]
+    fn parses_ath9k() {
+        let networks = parse(include_str!("../../fixtures/iw/ath9k.txt")).networks;
+        assert_eq!(networks.len(), 3);
+
+        let cafe = &networks[0];
+        assert_eq!(cafe.ssid, "cafe guest");
+        assert_eq!(cafe.mac, "00:1f:33:c0:ff:ee");
+        assert_eq!(cafe.channel, 1);
+        assert_eq!(auth(cafe), AuthType::Wpa);
+
+        let zon = &networks[1];
+        assert_eq!(zon.channel, 4);
+        assert_eq!(zon.frequency, Frequency::Freq2400MHz(2427));
+        assert_eq!(zon.beacon_interval, Some(102));
+        assert_eq!(auth(zon), AuthType::Wpa2);
+        assert_eq!(zon.bss_load.unwrap().station_count, 12);
+        assert_eq!(zon.bss_load.unwrap().channel_utilization, 181);
+        assert_eq!(zon.country.as_ref().unwrap().code, "PT");
+
+        let printer = &networks[2];
+        assert_eq!(printer.mac, "00:0f:b5:12:34:56");
+        assert_eq!(printer.signal, -85.0);
+        assert_eq!(auth(printer), AuthType::Wep);
+    }
+
+    #[test]
+    fn parses_brcmfmac() {
+        let networks = parse(include_str!("../../fixtures/iw/brcmfmac.txt")).networks;
+        assert_eq!(networks.len(), 3);
+
+        let lab = &networks[0];
+        assert_eq!(lab.ssid, "lab-5");
+        assert_eq!(lab.channel, 100);
+        assert_eq!(lab.width, Width::MHz160);
+        assert_eq!(lab.center_channel, Some(114));
+        assert_eq!(
+            lab.security.as_ref().unwrap().akm_suites,
+            vec![Akm::Ieee8021x]
+        );
+        assert!(lab.security.as_ref().unwrap().pmf_required);
+        assert_eq!(lab.country.as_ref().unwrap().power_limits.len(), 3);
+
+        // a hidden network still has an (empty) SSID element
+        let hidden = &networks[1];
+        assert_eq!(hidden.ssid, "");
+        assert_eq!(hidden.channel, 149);
+        assert_eq!(auth(hidden), AuthType::Open);
+
+        assert_eq!(networks[2].ssid, "warehouse-a");
+        assert_eq!(networks[2].channel, 52);
+    }
+
+    #[test]
+    fn parses_iwlwifi() {
+        let networks = parse(include_str!("../../fixtures/iw/iwlwifi.txt")).networks;
+        assert_eq!(networks.len(), 3);
+
+        let office_5g = &networks[0];
+        assert_eq!(office_5g.ssid, "Office 5G");
+        assert_eq!(office_5g.mac, "3c:37:86:1a:2b:3c");
+        assert_eq!(office_5g.width, Width::MHz80);
+        assert_eq!(office_5g.center_channel, Some(42));
+        assert_eq!(auth(office_5g), AuthType::Wpa3Transition);
+        assert_eq!(office_5g.bss_load.unwrap().station_count, 7);
+
+        let office = &networks[1];
+        assert_eq!(office.ssid, "Office");
+        assert_eq!(office.channel, 6);
+        assert_eq!(office.signal, -47.0);
+
+        // HT40 with the secondary channel below
+        let neighbour = &networks[2];
+        assert_eq!(neighbour.channel, 11);
+        assert_eq!(neighbour.width, Width::MHz40);
+        assert_eq!(neighbour.center_channel, Some(9));
+        assert!(neighbour.security.as_ref().unwrap().wps);
+    }
+
+    #[test]
+    fn parses_mt7921e() {
+        let networks = parse(include_str!("../../fixtures/iw/mt7921e.txt")).networks;
+        assert_eq!(networks.len(), 2);
+
+        let home_6e = &networks[0];
+        assert_eq!(home_6e.ssid, "home-6e");
+        assert_eq!(home_6e.frequency, Frequency::Freq6000MHz(5955));
+        assert_eq!(home_6e.channel, 1);
+        assert_eq!(home_6e.width, Width::MHz160);
+        assert_eq!(home_6e.center_channel, Some(15));
+        assert_eq!(auth(home_6e), AuthType::Wpa3);
+
+        let home = &networks[1];
+        assert_eq!(home.channel, 44);
+        assert_eq!(home.width, Width::MHz80);
+        assert_eq!(home.center_channel, Some(42));
+        assert_eq!(auth(home), AuthType::Wpa3Transition);
+    }
+
+    #[test]
+    fn reports_quirks() {
+        let parsed = parse_scan(include_str!("../../fixtures/iw/quirks.txt"));
+
+        let warnings = parsed
+            .warnings
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                "BSS 02:11:22:33:44:01: no SSID element",
+                "BSS 02:11:22:33:44:01: no STA channel width, assuming 20 MHz",
+                "BSS 02:11:22:33:44:02: dropped, Error parsing frequency.",
+            ]
+        );
+
+        // the one without a frequency is dropped, the others kept
+        assert_eq!(parsed.networks.len(), 2);
+        assert_eq!(parsed.networks[0].ssid, "");
+        assert_eq!(parsed.networks[0].width, Width::MHz20);
+        assert_eq!(parsed.networks[1].ssid, "café");
+        assert_eq!(parsed.networks[1].channel, 36);
+    }
+}
@@ -1,4 +1,14 @@
+pub mod benchmark;
 pub mod daemon;
-pub mod reading;
-mod scanner;
-mod wifi;
+mod ie;
+mod iw;
+mod nl80211;
+pub mod render;
+pub mod scanner;
+pub mod source;
+mod survey;
+
+// the model and the planning, shared with the server
+pub use ap_scanner_core::{
+    bss, diff, fleet, matrix, measure, planner, reading, regulatory, security, site, wifi,
+};
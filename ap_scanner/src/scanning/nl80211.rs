@@ -0,0 +1,767 @@
+// Scanning through nl80211 over generic netlink, without going through the `iw` binary.
+//
+// The flow mirrors what `iw dev <iface> scan` does:
+//  - resolve the nl80211 family id and its "scan" multicast group
+//  - pick the first station interface
+//  - trigger a scan and wait for the kernel to announce new results
+//  - dump the results and decode every BSS into a `Wifi`
+//
+// Decoding works on the raw bytes returned by the kernel, so recorded dumps can be fed to
+// `decode_scan_dump` and `decode_survey_dump` without a radio.
+use super::{
+    bss::Capability,
+    ie::{ChannelBlock, InformationElements},
+    iw::Warning,
+    security::Security,
+    survey::ChannelSurvey,
+    wifi::{Frequency, Width, Wifi},
+};
+use anyhow::{anyhow, bail, Context, Result};
+use std::{io, mem, os::unix::io::RawFd, time::Duration};
+
+// netlink
+const NLMSG_HDRLEN: usize = 16;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_ACK: u16 = 0x4;
+const NLM_F_DUMP: u16 = 0x300;
+const NLA_HDRLEN: usize = 4;
+const NLA_TYPE_MASK: u16 = 0x3fff;
+
+// generic netlink controller
+const GENL_HDRLEN: usize = 4;
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+const CTRL_ATTR_MCAST_GROUPS: u16 = 7;
+const CTRL_ATTR_MCAST_GRP_NAME: u16 = 1;
+const CTRL_ATTR_MCAST_GRP_ID: u16 = 2;
+
+// nl80211
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_GET_SCAN: u8 = 32;
+const NL80211_CMD_TRIGGER_SCAN: u8 = 33;
+const NL80211_CMD_NEW_SCAN_RESULTS: u8 = 34;
+const NL80211_CMD_SCAN_ABORTED: u8 = 35;
+const NL80211_CMD_GET_SURVEY: u8 = 50;
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_IFTYPE: u16 = 5;
+const NL80211_ATTR_BSS: u16 = 47;
+const NL80211_ATTR_SURVEY_INFO: u16 = 84;
+const NL80211_IFTYPE_STATION: u32 = 2;
+
+// nested in NL80211_ATTR_BSS
+const NL80211_BSS_BSSID: u16 = 1;
+const NL80211_BSS_FREQUENCY: u16 = 2;
+const NL80211_BSS_BEACON_INTERVAL: u16 = 4;
+const NL80211_BSS_CAPABILITY: u16 = 5;
+const NL80211_BSS_INFORMATION_ELEMENTS: u16 = 6;
+const NL80211_BSS_SIGNAL_MBM: u16 = 7;
+const NL80211_BSS_BEACON_IES: u16 = 11;
+
+// nested in NL80211_ATTR_SURVEY_INFO
+const NL80211_SURVEY_INFO_FREQUENCY: u16 = 1;
+const NL80211_SURVEY_INFO_NOISE: u16 = 2;
+const NL80211_SURVEY_INFO_IN_USE: u16 = 3;
+const NL80211_SURVEY_INFO_TIME: u16 = 4;
+const NL80211_SURVEY_INFO_TIME_BUSY: u16 = 5;
+const NL80211_SURVEY_INFO_TIME_RX: u16 = 7;
+const NL80211_SURVEY_INFO_TIME_TX: u16 = 8;
+
+// a full scan over both bands usually takes a few seconds
+const SCAN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Triggers a scan on the first station interface and returns the decoded results, along with
+/// why the entries that couldn't be decoded were dropped.
+pub fn scan() -> Result<(Vec<Wifi>, Vec<Warning>)> {
+    let mut socket = Socket::open()?;
+    let family = socket.resolve_family()?;
+    let ifindex = socket.station_interface(family.id)?;
+
+    // join before triggering, otherwise the announcement can be missed
+    socket.join_group(family.scan_group)?;
+
+    let trigger = socket.request(
+        family.id,
+        NLM_F_ACK,
+        NL80211_CMD_TRIGGER_SCAN,
+        &attr_u32(NL80211_ATTR_IFINDEX, ifindex),
+    );
+
+    match trigger {
+        Ok(_) => socket.wait_for_scan(family.id)?,
+        Err(err) => match os_error(&err) {
+            // a scan is already running, its results are just as good
+            Some(libc::EBUSY) => socket.wait_for_scan(family.id)?,
+            // the kernel would still hand out what it has cached, but those results may be stale
+            Some(libc::EPERM) => {
+                return Err(err.context("Not permitted to trigger a scan, it takes CAP_NET_ADMIN."))
+            }
+            _ => return Err(err.context("Failed to trigger a scan.")),
+        },
+    }
+
+    let dump = socket
+        .request(
+            family.id,
+            NLM_F_DUMP,
+            NL80211_CMD_GET_SCAN,
+            &attr_u32(NL80211_ATTR_IFINDEX, ifindex),
+        )
+        .with_context(|| anyhow!("Failed to fetch scan results."))?;
+
+    Ok(decode_scan_dump(&dump))
+}
+
+/// Decodes every BSS in a `NL80211_CMD_GET_SCAN` dump.
+/// Entries that are missing mandatory fields are dropped, with a warning saying why.
+pub fn decode_scan_dump(buf: &[u8]) -> (Vec<Wifi>, Vec<Warning>) {
+    let mut networks = vec![];
+    let mut warnings = vec![];
+
+    for (_, bss) in Messages(buf)
+        .filter_map(|(_, body)| genl_attrs(body))
+        .filter_map(|mut attrs| attrs.find(|(kind, _)| *kind == NL80211_ATTR_BSS))
+    {
+        match decode_bss(bss) {
+            Ok(wifi) => networks.push(wifi),
+            Err(err) => warnings.push(Warning {
+                mac: bssid(bss).unwrap_or_else(|| "?".to_string()),
+                message: format!("dropped, {:#}", err),
+            }),
+        }
+    }
+
+    (networks, warnings)
+}
+
+/// Fetches the survey data the driver keeps for every channel of the first station interface.
+pub fn survey() -> Result<Vec<ChannelSurvey>> {
+    let mut socket = Socket::open()?;
+    let family = socket.resolve_family()?;
+    let ifindex = socket.station_interface(family.id)?;
+
+    let dump = socket
+        .request(
+            family.id,
+            NLM_F_DUMP,
+            NL80211_CMD_GET_SURVEY,
+            &attr_u32(NL80211_ATTR_IFINDEX, ifindex),
+        )
+        .with_context(|| anyhow!("Failed to fetch survey data."))?;
+
+    Ok(decode_survey_dump(&dump))
+}
+
+/// Decodes every channel in a `NL80211_CMD_GET_SURVEY` dump.
+/// Entries without a frequency are skipped.
+pub fn decode_survey_dump(buf: &[u8]) -> Vec<ChannelSurvey> {
+    Messages(buf)
+        .filter_map(|(_, body)| genl_attrs(body))
+        .filter_map(|mut attrs| attrs.find(|(kind, _)| *kind == NL80211_ATTR_SURVEY_INFO))
+        .filter_map(|(_, info)| {
+            let mut survey = ChannelSurvey::default();
+
+            for (kind, payload) in Attributes(info) {
+                match kind {
+                    NL80211_SURVEY_INFO_FREQUENCY => {
+                        survey.frequency = read_u32(payload).unwrap_or_default() as u16
+                    }
+                    NL80211_SURVEY_INFO_NOISE => survey.noise = payload.first().map(|n| *n as i8),
+                    NL80211_SURVEY_INFO_IN_USE => survey.in_use = true,
+                    NL80211_SURVEY_INFO_TIME => survey.active_time = read_u64(payload),
+                    NL80211_SURVEY_INFO_TIME_BUSY => survey.busy_time = read_u64(payload),
+                    NL80211_SURVEY_INFO_TIME_RX => survey.receive_time = read_u64(payload),
+                    NL80211_SURVEY_INFO_TIME_TX => survey.transmit_time = read_u64(payload),
+                    _ => {}
+                }
+            }
+
+            Some(survey).filter(|survey| survey.frequency != 0)
+        })
+        .collect()
+}
+
+/// The BSSID of a BSS, if it has one.
+fn bssid(bss: &[u8]) -> Option<String> {
+    Attributes(bss)
+        .find(|(kind, _)| *kind == NL80211_BSS_BSSID)
+        .map(|(_, payload)| format_mac(payload))
+}
+
+fn decode_bss(bss: &[u8]) -> Result<Wifi> {
+    let mut mac = None;
+    let mut freq = None;
+    let mut signal = None;
+    let mut beacon_interval = None;
+    let mut capability = None;
+    let mut ies = None;
+    let mut beacon_ies = None;
+
+    for (kind, payload) in Attributes(bss) {
+        match kind {
+            NL80211_BSS_BSSID => mac = Some(format_mac(payload)),
+            NL80211_BSS_FREQUENCY => freq = read_u32(payload),
+            NL80211_BSS_BEACON_INTERVAL => beacon_interval = read_u16(payload),
+            NL80211_BSS_CAPABILITY => capability = read_u16(payload).map(Capability),
+            // mBm, hundredths of a dBm
+            NL80211_BSS_SIGNAL_MBM => signal = read_u32(payload).map(|v| v as i32 as f32 / 100.0),
+            NL80211_BSS_INFORMATION_ELEMENTS => ies = Some(payload),
+            NL80211_BSS_BEACON_IES => beacon_ies = Some(payload),
+            _ => {}
+        }
+    }
+
+    let mac = mac.with_context(|| anyhow!("Error parsing BSS."))?;
+    let frequency = freq
+        .map(|freq| Frequency::from(freq as u16))
+        .with_context(|| anyhow!("Error parsing frequency."))?;
+    let signal = signal.with_context(|| anyhow!("Error parsing signal."))?;
+
+    // probe responses carry the most complete set of elements, beacons are the fallback
+    let ies = InformationElements::new(ies.or(beacon_ies).unwrap_or_default());
+    let ht = ies.ht_operation();
+    let he_6ghz = ies.he_6ghz_operation();
+
+    // legacy APs have no HT operation, and 802.11a ones not even a DS Parameter Set
+    let channel = ht
+        .as_ref()
+        .map(|ht| ht.primary_channel)
+        .or_else(|| he_6ghz.as_ref().map(|(primary, _)| *primary))
+        .or_else(|| ies.ds_channel())
+        .or_else(|| frequency.channel())
+        .with_context(|| anyhow!("Couldn't parse channel."))?;
+
+    // the newest generation of operation element describes the real block
+    let block = ies
+        .eht_operation()
+        .or_else(|| he_6ghz.map(|(_, block)| block))
+        .or_else(|| ies.vht_operation())
+        .or_else(|| ht.as_ref().map(ChannelBlock::ht))
+        .unwrap_or_else(|| ChannelBlock::new(Width::MHz20, channel));
+
+    Ok(Wifi {
+        ssid: ies.ssid().unwrap_or_default(),
+        mac,
+        channel,
+        signal,
+        frequency,
+        width: block.width,
+        center_channel: Some(block.center),
+        center_channel_2: block.center_2,
+        security: Some(Security::new(
+            ies.rsn(),
+            ies.wpa(),
+            capability
+                .map(|caps| caps.has(Capability::PRIVACY))
+                .unwrap_or_default(),
+            ies.wps(),
+        )),
+        bss_load: ies.bss_load(),
+        country: ies.country(),
+        beacon_interval,
+        capability,
+    })
+}
+
+struct Family {
+    id: u16,
+    scan_group: u32,
+}
+
+struct Socket {
+    fd: RawFd,
+    seq: u32,
+}
+
+impl Socket {
+    fn open() -> Result<Self> {
+        // SAFETY: plain syscalls, the address is fully initialised before use
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_GENERIC,
+            )
+        };
+
+        if fd < 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| anyhow!("Failed to open a netlink socket."));
+        }
+
+        let socket = Self { fd, seq: 0 };
+
+        let mut addr: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as u16;
+
+        let res = unsafe {
+            libc::bind(
+                fd,
+                &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as u32,
+            )
+        };
+
+        if res < 0 {
+            return Err(io::Error::last_os_error())
+                .with_context(|| anyhow!("Failed to bind the netlink socket."));
+        }
+
+        socket.set_option(
+            libc::SOL_SOCKET,
+            libc::SO_RCVTIMEO,
+            &libc::timeval {
+                tv_sec: SCAN_TIMEOUT.as_secs() as libc::time_t,
+                tv_usec: 0,
+            },
+        )?;
+
+        Ok(socket)
+    }
+
+    fn set_option<T>(&self, level: i32, name: i32, value: &T) -> Result<()> {
+        let res = unsafe {
+            libc::setsockopt(
+                self.fd,
+                level,
+                name,
+                value as *const T as *const libc::c_void,
+                mem::size_of::<T>() as u32,
+            )
+        };
+
+        if res < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        Ok(())
+    }
+
+    fn join_group(&self, group: u32) -> Result<()> {
+        self.set_option(libc::SOL_NETLINK, libc::NETLINK_ADD_MEMBERSHIP, &group)
+            .with_context(|| anyhow!("Failed to join the nl80211 scan group."))
+    }
+
+    fn send(&mut self, family: u16, flags: u16, cmd: u8, attrs: &[u8]) -> Result<u32> {
+        self.seq += 1;
+
+        let len = NLMSG_HDRLEN + GENL_HDRLEN + attrs.len();
+        let mut msg = Vec::with_capacity(len);
+        msg.extend_from_slice(&(len as u32).to_ne_bytes());
+        msg.extend_from_slice(&family.to_ne_bytes());
+        msg.extend_from_slice(&(NLM_F_REQUEST | flags).to_ne_bytes());
+        msg.extend_from_slice(&self.seq.to_ne_bytes());
+        msg.extend_from_slice(&0u32.to_ne_bytes());
+        // genl header: command, version, reserved
+        msg.extend_from_slice(&[cmd, 1, 0, 0]);
+        msg.extend_from_slice(attrs);
+
+        let res = unsafe { libc::send(self.fd, msg.as_ptr() as *const libc::c_void, msg.len(), 0) };
+
+        if res < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        Ok(self.seq)
+    }
+
+    fn recv(&self, buf: &mut [u8]) -> Result<usize> {
+        let res =
+            unsafe { libc::recv(self.fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+
+        if res < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        Ok(res as usize)
+    }
+
+    /// Sends a request and collects the raw replies belonging to it.
+    /// Stops at the acknowledgement, at the end of a dump, or after the first reply otherwise.
+    fn request(&mut self, family: u16, flags: u16, cmd: u8, attrs: &[u8]) -> Result<Vec<u8>> {
+        let seq = self.send(family, flags, cmd, attrs)?;
+        let mut buf = vec![0u8; 32 * 1024];
+        let mut out = vec![];
+
+        loop {
+            let len = self.recv(&mut buf)?;
+
+            for msg in MessagesRaw(&buf[..len]) {
+                let header = Header::parse(msg);
+
+                // multicast notifications share the socket, they're not ours
+                if header.seq != seq {
+                    continue;
+                }
+
+                match header.kind {
+                    NLMSG_DONE => return Ok(out),
+                    NLMSG_ERROR => {
+                        let err = read_u32(&msg[NLMSG_HDRLEN..]).unwrap_or_default() as i32;
+
+                        if err != 0 {
+                            return Err(io::Error::from_raw_os_error(-err).into());
+                        }
+
+                        return Ok(out);
+                    }
+                    _ => out.extend_from_slice(msg),
+                }
+            }
+
+            if flags & (NLM_F_ACK | NLM_F_DUMP) == 0 && !out.is_empty() {
+                return Ok(out);
+            }
+        }
+    }
+
+    fn resolve_family(&mut self) -> Result<Family> {
+        let name = attr(CTRL_ATTR_FAMILY_NAME, b"nl80211\0");
+
+        let reply = self
+            .request(GENL_ID_CTRL, 0, CTRL_CMD_GETFAMILY, &name)
+            .with_context(|| anyhow!("nl80211 is not available."))?;
+
+        let mut id = None;
+        let mut scan_group = None;
+
+        for (kind, payload) in Messages(&reply)
+            .filter_map(|(_, body)| genl_attrs(body))
+            .flatten()
+        {
+            match kind {
+                CTRL_ATTR_FAMILY_ID => id = read_u16(payload),
+                CTRL_ATTR_MCAST_GROUPS => {
+                    scan_group = Attributes(payload)
+                        .map(|(_, group)| {
+                            let mut name = None;
+                            let mut id = None;
+
+                            for (kind, payload) in Attributes(group) {
+                                match kind {
+                                    CTRL_ATTR_MCAST_GRP_NAME => name = Some(payload),
+                                    CTRL_ATTR_MCAST_GRP_ID => id = read_u32(payload),
+                                    _ => {}
+                                }
+                            }
+
+                            (name, id)
+                        })
+                        .find(|(name, _)| *name == Some(b"scan\0".as_ref()))
+                        .and_then(|(_, id)| id)
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Family {
+            id: id.with_context(|| anyhow!("nl80211 family id missing."))?,
+            scan_group: scan_group.with_context(|| anyhow!("nl80211 scan group missing."))?,
+        })
+    }
+
+    fn station_interface(&mut self, family: u16) -> Result<u32> {
+        let dump = self.request(family, NLM_F_DUMP, NL80211_CMD_GET_INTERFACE, &[])?;
+
+        Messages(&dump)
+            .filter_map(|(_, body)| genl_attrs(body))
+            .find_map(|attrs| {
+                let mut ifindex = None;
+                let mut iftype = None;
+
+                for (kind, payload) in attrs {
+                    match kind {
+                        NL80211_ATTR_IFINDEX => ifindex = read_u32(payload),
+                        NL80211_ATTR_IFTYPE => iftype = read_u32(payload),
+                        _ => {}
+                    }
+                }
+
+                ifindex.filter(|_| iftype == Some(NL80211_IFTYPE_STATION))
+            })
+            .with_context(|| anyhow!("No interface found."))
+    }
+
+    fn wait_for_scan(&self, family: u16) -> Result<()> {
+        let mut buf = vec![0u8; 8 * 1024];
+
+        loop {
+            let len = self
+                .recv(&mut buf)
+                .with_context(|| anyhow!("Timed out waiting for scan results."))?;
+
+            for (kind, body) in Messages(&buf[..len]) {
+                match (kind, body.first().copied()) {
+                    (k, Some(NL80211_CMD_NEW_SCAN_RESULTS)) if k == family => return Ok(()),
+                    (k, Some(NL80211_CMD_SCAN_ABORTED)) if k == family => bail!("Scan aborted."),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
+
+fn os_error(err: &anyhow::Error) -> Option<i32> {
+    err.downcast_ref::<io::Error>()
+        .and_then(io::Error::raw_os_error)
+}
+
+struct Header {
+    kind: u16,
+    seq: u32,
+}
+
+impl Header {
+    fn parse(msg: &[u8]) -> Self {
+        Self {
+            kind: read_u16(&msg[4..]).unwrap_or_default(),
+            seq: read_u32(&msg[8..]).unwrap_or_default(),
+        }
+    }
+}
+
+/// Splits a buffer into whole netlink messages, header included.
+struct MessagesRaw<'a>(&'a [u8]);
+
+impl<'a> Iterator for MessagesRaw<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = read_u32(self.0)? as usize;
+
+        if len < NLMSG_HDRLEN || len > self.0.len() {
+            self.0 = &[];
+            return None;
+        }
+
+        let msg = &self.0[..len];
+        self.0 = &self.0[align(len).min(self.0.len())..];
+
+        Some(msg)
+    }
+}
+
+/// Splits a buffer into `(message type, body)` pairs.
+struct Messages<'a>(&'a [u8]);
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut raw = MessagesRaw(self.0);
+        let msg = raw.next()?;
+        self.0 = raw.0;
+
+        Some((Header::parse(msg).kind, &msg[NLMSG_HDRLEN..]))
+    }
+}
+
+/// Iterates over a run of netlink attributes as `(type, payload)` pairs.
+struct Attributes<'a>(&'a [u8]);
+
+impl<'a> Iterator for Attributes<'a> {
+    type Item = (u16, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = read_u16(self.0)? as usize;
+        let kind = read_u16(self.0.get(2..)?)? & NLA_TYPE_MASK;
+
+        if len < NLA_HDRLEN || len > self.0.len() {
+            self.0 = &[];
+            return None;
+        }
+
+        let payload = &self.0[NLA_HDRLEN..len];
+        self.0 = &self.0[align(len).min(self.0.len())..];
+
+        Some((kind, payload))
+    }
+}
+
+/// The attributes following the generic netlink header of a message body.
+fn genl_attrs(body: &[u8]) -> Option<Attributes<'_>> {
+    body.get(GENL_HDRLEN..).map(Attributes)
+}
+
+fn attr(kind: u16, payload: &[u8]) -> Vec<u8> {
+    let len = NLA_HDRLEN + payload.len();
+    let mut out = Vec::with_capacity(align(len));
+    out.extend_from_slice(&(len as u16).to_ne_bytes());
+    out.extend_from_slice(&kind.to_ne_bytes());
+    out.extend_from_slice(payload);
+    out.resize(align(len), 0);
+    out
+}
+
+fn attr_u32(kind: u16, value: u32) -> Vec<u8> {
+    attr(kind, &value.to_ne_bytes())
+}
+
+#[inline]
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn read_u16(buf: &[u8]) -> Option<u16> {
+    buf.get(..2).map(|b| u16::from_ne_bytes([b[0], b[1]]))
+}
+
+fn read_u32(buf: &[u8]) -> Option<u32> {
+    buf.get(..4)
+        .map(|b| u32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_u64(buf: &[u8]) -> Option<u64> {
+    buf.get(..8)
+        .map(|b| u64::from_ne_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+}
+
+fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanning::{
+        bss::PowerLimit,
+        security::{Akm, AuthType},
+    };
+
+    // dumps in the layout the kernel sends them, little endian
+    const SCAN: &[u8] = include_bytes!("../../fixtures/nl80211/scan.bin");
+    const SURVEY: &[u8] = include_bytes!("../../fixtures/nl80211/survey.bin");
+
+    #[test]
+    fn decodes_scan_dump() {
+        let (networks, warnings) = decode_scan_dump(SCAN);
+
+        // the entry without a BSSID is dropped, and said so
+        assert_eq!(networks.len(), 4);
+        assert_eq!(
+            warnings.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["BSS ?: dropped, Error parsing BSS."]
+        );
+
+        let office = &networks[0];
+        assert_eq!(office.ssid, "Office");
+        assert_eq!(office.mac, "3c:37:86:1a:2b:3d");
+        assert_eq!(office.frequency, Frequency::Freq2400MHz(2437));
+        assert_eq!(office.channel, 6);
+        assert_eq!(office.signal, -47.0);
+        assert_eq!(office.width, Width::MHz20);
+        assert_eq!(office.center_channel, Some(6));
+        assert_eq!(office.beacon_interval, Some(100));
+        assert_eq!(office.capability, Some(Capability(0x0411)));
+
+        let security = office.security.as_ref().unwrap();
+        assert_eq!(security.auth, AuthType::Wpa2);
+        assert_eq!(security.akm_suites, vec![Akm::Psk]);
+        assert!(security.wps);
+
+        let load = office.bss_load.unwrap();
+        assert_eq!(load.station_count, 3);
+        assert_eq!(load.channel_utilization, 102);
+
+        let country = office.country.as_ref().unwrap();
+        assert_eq!(country.code, "PT");
+        assert_eq!(
+            country.power_limits,
+            vec![PowerLimit {
+                first_channel: 1,
+                last_channel: 13,
+                max_power_dbm: 20
+            }]
+        );
+    }
+
+    #[test]
+    fn decodes_beacon_elements_without_probe_response() {
+        let office_5g = &decode_scan_dump(SCAN).0[1];
+
+        assert_eq!(office_5g.ssid, "Office 5G");
+        assert_eq!(office_5g.channel, 36);
+        assert_eq!(office_5g.width, Width::MHz80);
+        assert_eq!(office_5g.center_channel, Some(42));
+
+        let security = office_5g.security.as_ref().unwrap();
+        assert_eq!(security.auth, AuthType::Wpa3Transition);
+        assert!(security.pmf_capable);
+        assert_eq!(
+            office_5g.country.as_ref().unwrap().power_limits[0].last_channel,
+            64
+        );
+    }
+
+    #[test]
+    fn decodes_legacy_and_6ghz_bss() {
+        let (networks, _) = decode_scan_dump(SCAN);
+
+        // 802.11a, no HT operation nor DS Parameter Set
+        let cafe = &networks[2];
+        assert_eq!(cafe.channel, 149);
+        assert_eq!(cafe.width, Width::MHz20);
+        assert_eq!(cafe.signal, -78.5);
+        assert_eq!(cafe.security.as_ref().unwrap().auth, AuthType::Open);
+
+        let lab = &networks[3];
+        assert_eq!(lab.frequency, Frequency::Freq6000MHz(5975));
+        assert_eq!(lab.channel, 5);
+        assert_eq!(lab.width, Width::MHz80);
+        assert_eq!(lab.center_channel, Some(7));
+        assert_eq!(lab.security.as_ref().unwrap().auth, AuthType::Wpa3);
+        assert!(lab.security.as_ref().unwrap().pmf_required);
+    }
+
+    #[test]
+    fn truncated_dump_does_not_panic() {
+        for len in 0..SCAN.len() {
+            assert!(decode_scan_dump(&SCAN[..len]).0.len() <= 4);
+        }
+    }
+
+    #[test]
+    fn decodes_survey_dump() {
+        let survey = decode_survey_dump(SURVEY);
+
+        // the entry without a frequency is skipped
+        assert_eq!(survey.len(), 4);
+
+        assert_eq!(
+            survey[0],
+            ChannelSurvey {
+                frequency: 2412,
+                in_use: false,
+                noise: Some(-95),
+                active_time: Some(1000),
+                busy_time: Some(300),
+                receive_time: Some(200),
+                transmit_time: Some(20),
+            }
+        );
+
+        assert!(survey[1].in_use);
+        assert_eq!(survey[1].busy_ratio(), Some(0.5));
+        assert_eq!(survey[2].noise, Some(-101));
+        assert_eq!(
+            survey[3],
+            ChannelSurvey {
+                frequency: 5200,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use super::{center_mhz, channel_mhz, networks, span_mhz, suggested, suggested_label, Renderer};
+use crate::scanning::reading::Reading;
+use crate::scanning::regulatory::{Band, Domain};
+use std::fmt::Write;
+
+/// Every BSS drawn as an arc over the channels it occupies, as high as it is heard, the way
+/// Wifi analyzer apps do, in plain text for a terminal. With `suggested` the arcs are drawn again
+/// in dots on the suggested channels, with the label of the network at their peak.
+/// 6 GHz networks are left out, their band doesn't fit in a terminal.
+#[derive(Default)]
+pub struct Chart {
+    pub suggested: bool,
+}
+
+/// A chart, a band or the part of one with channels in it, in MHz.
+struct Axis {
+    band: Band,
+    name: &'static str,
+    low: f32,
+    high: f32,
+}
+
+// the 5 GHz band is split around the channels no one may use
+const AXES: [Axis; 3] = [
+    Axis {
+        band: Band::Ghz2_4,
+        name: "2.4GHz",
+        low: 2400.0,
+        high: 2490.0,
+    },
+    Axis {
+        band: Band::Ghz5,
+        name: "5GHz, channels 36 to 64",
+        low: 5150.0,
+        high: 5350.0,
+    },
+    Axis {
+        band: Band::Ghz5,
+        name: "5GHz, channels 100 to 177",
+        low: 5470.0,
+        high: 5895.0,
+    },
+];
+
+// size of the plot, fits a 80 columns terminal with the labels
+const COLUMNS: usize = 72;
+const ROWS: usize = 16;
+
+// the signal axis, in dBm, the floor is the channel axis
+const FLOOR_DBM: f32 = -100.0;
+const TOP_DBM: f32 = -20.0;
+
+// what BSSs are drawn with, in order
+const LABELS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+// what the arc of a suggestion is drawn with
+const SUGGESTED: char = ':';
+
+/// The chart of `band` closest to `mhz`.
+fn axis(band: Band, mhz: f32) -> Option<usize> {
+    let outside = |axis: &Axis| (axis.low - mhz).max(mhz - axis.high).max(0.0);
+
+    (0..AXES.len())
+        .filter(|index| AXES[*index].band == band)
+        .min_by(|a, b| {
+            outside(&AXES[*a])
+                .partial_cmp(&outside(&AXES[*b]))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+/// The character a BSS is drawn with, a star once all of `LABELS` are taken.
+fn label(index: usize) -> char {
+    LABELS.get(index).map_or('*', |label| *label as char)
+}
+
+/// The row of the plot `dbm` falls on, `ROWS` on the channel axis.
+fn row(dbm: f32) -> usize {
+    let step = (TOP_DBM - FLOOR_DBM) / ROWS as f32;
+
+    (((TOP_DBM - dbm.max(FLOOR_DBM)) / step).round().max(0.0) as usize).min(ROWS)
+}
+
+struct Plot<'a> {
+    axis: &'a Axis,
+    cells: Vec<Vec<char>>,
+}
+
+impl<'a> Plot<'a> {
+    fn new(axis: &'a Axis) -> Self {
+        Self {
+            axis,
+            cells: vec![vec![' '; COLUMNS]; ROWS],
+        }
+    }
+
+    /// Frequency at the middle of a column, in MHz.
+    fn mhz(&self, column: usize) -> f32 {
+        let per_column = (self.axis.high - self.axis.low) / COLUMNS as f32;
+        self.axis.low + (column as f32 + 0.5) * per_column
+    }
+
+    fn column(&self, mhz: f32) -> Option<usize> {
+        let column = (mhz - self.axis.low) / (self.axis.high - self.axis.low) * COLUMNS as f32;
+
+        match column >= 0.0 && column < COLUMNS as f32 {
+            true => Some(column as usize),
+            false => None,
+        }
+    }
+
+    /// Draws a parabola over `span` MHz around `center`, peaking at `signal`.
+    fn arc(&mut self, center: f32, span: f32, signal: f32, symbol: char) {
+        let height = |mhz: f32| {
+            let offset = 2.0 * (mhz - center) / span;
+
+            match offset.abs() <= 1.0 {
+                true => row(FLOOR_DBM + (signal - FLOOR_DBM) * (1.0 - offset * offset)),
+                false => ROWS,
+            }
+        };
+
+        let rows = (0..COLUMNS)
+            .map(|column| height(self.mhz(column)))
+            .collect::<Vec<_>>();
+
+        for (column, top) in rows.iter().enumerate() {
+            if *top >= ROWS {
+                continue;
+            }
+
+            // down to the neighbours, so steep sides are drawn in full
+            let left = column.checked_sub(1).map(|left| rows[left]).unwrap_or(ROWS);
+            let right = rows.get(column + 1).copied().unwrap_or(ROWS);
+            let bottom = (left.max(right).saturating_sub(1)).clamp(*top, ROWS - 1);
+
+            for line in &mut self.cells[*top..=bottom] {
+                line[column] = symbol;
+            }
+        }
+    }
+
+    /// Writes `symbol` at the peak of the arc around `center`.
+    fn mark(&mut self, center: f32, signal: f32, symbol: char) {
+        if let Some(column) = self.column(center) {
+            self.cells[row(signal).min(ROWS - 1)][column] = symbol;
+        }
+    }
+
+    fn write(&self, output: &mut String, domain: &Domain) -> std::fmt::Result {
+        let step = (TOP_DBM - FLOOR_DBM) / ROWS as f32;
+
+        for (index, line) in self.cells.iter().enumerate() {
+            let scale = match index % 4 {
+                0 => format!("{:.0}", TOP_DBM - index as f32 * step),
+                _ => String::new(),
+            };
+
+            let line = line.iter().collect::<String>();
+            writeln!(output, "{:>4} |{}", scale, line.trim_end())?;
+        }
+
+        writeln!(output, "{:>4} +{}", FLOOR_DBM, "-".repeat(COLUMNS))?;
+
+        // channel numbers where there is room for them
+        let mut channels = vec![' '; COLUMNS];
+        for channel in domain.channels(self.axis.band) {
+            let text = channel.to_string();
+
+            let start = match self.column(channel_mhz(self.axis.band, channel)) {
+                Some(column) => column.saturating_sub(text.len() / 2),
+                None => continue,
+            };
+            let end = start + text.len();
+
+            if end <= COLUMNS
+                && channels[start.saturating_sub(1)..(end + 1).min(COLUMNS)]
+                    .iter()
+                    .all(|cell| *cell == ' ')
+            {
+                channels.splice(start..end, text.chars());
+            }
+        }
+
+        writeln!(
+            output,
+            "{:>4}  {}",
+            "",
+            channels.iter().collect::<String>().trim_end()
+        )
+    }
+}
+
+impl Renderer for Chart {
+    fn render(&self, reading: &Reading) -> anyhow::Result<String> {
+        let domain = Domain::resolve(reading.country.as_deref());
+        let mut plots = AXES.iter().map(Plot::new).collect::<Vec<_>>();
+        let mut legends = vec![vec![]; AXES.len()];
+
+        let mut drawn = networks(reading)
+            .filter(|(wifi, _)| wifi.band() != Band::Ghz6)
+            .enumerate()
+            .map(|(index, (wifi, suggestion))| (label(index), wifi, suggestion))
+            .collect::<Vec<_>>();
+
+        for (symbol, wifi, suggestion) in &drawn {
+            let center = center_mhz(wifi);
+
+            if let Some(index) = axis(wifi.band(), center) {
+                let mut legend = format!(
+                    "{} {}, {} on channel {} at {}MHz, heard at {:.0} dBm",
+                    symbol,
+                    wifi.ssid,
+                    wifi.mac,
+                    wifi.channel,
+                    wifi.width.mhz(),
+                    wifi.signal
+                );
+
+                if self.suggested {
+                    write!(legend, ", suggested {}", suggested_label(wifi, suggestion))?;
+                }
+
+                legends[index].push(legend);
+            }
+
+            // suggestions first, a network where it is now is drawn over them
+            let target = suggested(wifi, suggestion)
+                .map(|(channel, _)| channel_mhz(wifi.band(), channel))
+                .filter(|target| self.suggested && *target != center);
+
+            if let Some(target) = target {
+                if let Some(index) = axis(wifi.band(), target) {
+                    plots[index].arc(target, span_mhz(wifi), wifi.signal, SUGGESTED);
+                    plots[index].mark(target, wifi.signal, *symbol);
+                }
+            }
+        }
+
+        // loudest first, the arcs of quieter networks on top of them
+        drawn.sort_by(|a, b| {
+            b.1.signal
+                .partial_cmp(&a.1.signal)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        for (symbol, wifi, _) in drawn {
+            let center = center_mhz(wifi);
+
+            if let Some(index) = axis(wifi.band(), center) {
+                plots[index].arc(center, span_mhz(wifi), wifi.signal, symbol);
+            }
+        }
+
+        let mut output = String::new();
+
+        for (plot, legend) in plots.iter().zip(&legends) {
+            if legend.is_empty() && plot.cells.iter().flatten().all(|cell| *cell == ' ') {
+                continue;
+            }
+
+            writeln!(output, "{}", plot.axis.name)?;
+            plot.write(&mut output, domain)?;
+
+            for line in legend {
+                writeln!(output, "\t{}", line)?;
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_go_past_the_alphabet() {
+        assert_eq!(label(0), 'A');
+        assert_eq!(label(26), 'a');
+        assert_eq!(label(52), '0');
+        assert_eq!(label(61), '9');
+        assert_eq!(label(62), '*');
+    }
+}
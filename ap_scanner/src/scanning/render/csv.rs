@@ -0,0 +1,39 @@
+use super::{networks, row, Renderer, COLUMNS};
+use crate::scanning::matrix::csv_field;
+use crate::scanning::reading::Reading;
+use std::fmt::Write;
+
+/// One line per BSS, after a header naming the columns.
+pub struct Csv;
+
+impl Renderer for Csv {
+    fn render(&self, reading: &Reading) -> anyhow::Result<String> {
+        let mut output = String::new();
+
+        writeln!(output, "{}", COLUMNS.join(","))?;
+
+        for (wifi, suggestion) in networks(reading) {
+            let fields = row(reading, wifi, suggestion).map(|value| csv_field(&value));
+
+            writeln!(output, "{}", fields.join(","))?;
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::reading;
+    use super::*;
+
+    #[test]
+    fn ssid_with_a_comma_is_quoted() {
+        let output = Csv.render(&reading()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert!(lines[1].starts_with("2.4GHz,\"Lab <a|b>, c\",3c:37:86:1a:2b:3d,6,20,-47,"));
+    }
+}
@@ -0,0 +1,300 @@
+use super::{
+    band_name, center_mhz, channel_mhz, networks, row, span_mhz, suggested, Renderer, COLUMNS,
+};
+use crate::scanning::reading::{Reading, Suggestion, WidthAdvice};
+use crate::scanning::regulatory::{self, Band, Domain};
+use crate::scanning::wifi::Wifi;
+use std::fmt::Write;
+
+/// A report in a single file, styles and charts included, readable in any browser.
+pub struct Html;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;font-size:0.9em}\
+th,td{border:1px solid #ccc;padding:0.3em 0.6em;text-align:left}\
+th{background:#f0f0f0}\
+pre{background:#f8f8f8;padding:0.6em}\
+svg{display:block;margin-bottom:1.5em}";
+
+// size of a chart and of the margin left for the axis labels, in pixels
+const WIDTH: f32 = 900.0;
+const HEIGHT: f32 = 260.0;
+const MARGIN: f32 = 40.0;
+
+// the signal axis, in dBm
+const FLOOR_DBM: f32 = -100.0;
+const TOP_DBM: f32 = -20.0;
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// The part of the spectrum a chart of `band` shows, in MHz.
+fn range(band: Band) -> (f32, f32) {
+    match band {
+        Band::Ghz2_4 => (2400.0, 2490.0),
+        Band::Ghz5 => (5150.0, 5895.0),
+        Band::Ghz6 => (5945.0, 7125.0),
+    }
+}
+
+/// Whether the channel is labelled on the chart, 6 GHz is too crowded for more than PSC.
+fn labelled(band: Band, channel: u8) -> bool {
+    match band {
+        Band::Ghz6 => regulatory::is_psc(channel),
+        _ => true,
+    }
+}
+
+/// A colour of its own for every BSS.
+fn hue(mac: &str) -> u32 {
+    mac.bytes().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(byte as u32)
+    }) % 360
+}
+
+fn x(band: Band, mhz: f32) -> f32 {
+    let (low, high) = range(band);
+    MARGIN + (mhz - low) / (high - low) * (WIDTH - 2.0 * MARGIN)
+}
+
+fn y(dbm: f32) -> f32 {
+    let dbm = dbm.clamp(FLOOR_DBM, TOP_DBM);
+    HEIGHT - MARGIN - (dbm - FLOOR_DBM) / (TOP_DBM - FLOOR_DBM) * (HEIGHT - 2.0 * MARGIN)
+}
+
+/// An arc over `span` MHz around `center`, as high as `signal`.
+fn arc(band: Band, center: f32, span: f32, signal: f32) -> String {
+    let base = y(FLOOR_DBM);
+    // the control point of a quadratic curve is twice as high as its peak
+    let control = 2.0 * y(signal) - base;
+
+    format!(
+        "M {:.1} {:.1} Q {:.1} {:.1} {:.1} {:.1}",
+        x(band, center - span / 2.0),
+        base,
+        x(band, center),
+        control,
+        x(band, center + span / 2.0),
+        base
+    )
+}
+
+fn write_chart(
+    output: &mut String,
+    band: Band,
+    domain: &Domain,
+    pairs: &[&(Wifi, Suggestion)],
+) -> std::fmt::Result {
+    writeln!(output, "<h2>{} spectrum</h2>", band_name(band))?;
+    writeln!(
+        output,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+        WIDTH, HEIGHT
+    )?;
+
+    // axes, with a line every 20 dB
+    let base = y(FLOOR_DBM);
+    writeln!(
+        output,
+        "<line x1=\"{0}\" y1=\"{1:.1}\" x2=\"{2}\" y2=\"{1:.1}\" stroke=\"#444\"/>",
+        MARGIN,
+        base,
+        WIDTH - MARGIN
+    )?;
+    for dbm in (FLOOR_DBM as i32..=TOP_DBM as i32).step_by(20) {
+        writeln!(
+            output,
+            "<line x1=\"{0}\" y1=\"{1:.1}\" x2=\"{2}\" y2=\"{1:.1}\" stroke=\"#eee\"/><text x=\"{3}\" y=\"{1:.1}\" font-size=\"10\" text-anchor=\"end\">{4}</text>",
+            MARGIN,
+            y(dbm as f32),
+            WIDTH - MARGIN,
+            MARGIN - 4.0,
+            dbm
+        )?;
+    }
+
+    for channel in domain.channels(band) {
+        if labelled(band, channel) {
+            writeln!(
+                output,
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"9\" text-anchor=\"middle\">{}</text>",
+                x(band, channel_mhz(band, channel)),
+                base + 14.0,
+                channel
+            )?;
+        }
+    }
+
+    // where the networks are now, and dashed where they are suggested to go
+    for (wifi, suggestion) in pairs {
+        let colour = format!("hsl({}, 70%, 45%)", hue(&wifi.mac));
+        let center = center_mhz(wifi);
+        let span = span_mhz(wifi);
+
+        writeln!(
+            output,
+            "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"><title>{} {}</title></path>",
+            arc(band, center, span, wifi.signal),
+            colour,
+            escape(&wifi.ssid),
+            escape(&wifi.mac)
+        )?;
+        writeln!(
+            output,
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"9\" fill=\"{}\" text-anchor=\"middle\">{}</text>",
+            x(band, center),
+            y(wifi.signal) - 4.0,
+            colour,
+            escape(&wifi.ssid)
+        )?;
+
+        if let Some((channel, _)) = suggested(wifi, suggestion) {
+            let target = channel_mhz(band, channel);
+
+            if target != center {
+                writeln!(
+                    output,
+                    "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-dasharray=\"4 3\"/>",
+                    arc(band, target, span, wifi.signal),
+                    colour
+                )?;
+            }
+        }
+    }
+
+    writeln!(output, "</svg>")
+}
+
+fn write_width(output: &mut String, band: &str, advice: Option<&WidthAdvice>) -> std::fmt::Result {
+    if let Some(advice) = advice {
+        writeln!(output, "<h3>{}</h3>", band)?;
+        writeln!(output, "<pre>{}</pre>", escape(&advice.to_string()))?;
+    }
+
+    Ok(())
+}
+
+impl Renderer for Html {
+    fn render(&self, reading: &Reading) -> anyhow::Result<String> {
+        let mut output = String::new();
+        let domain = Domain::resolve(reading.country.as_deref());
+        let title = format!("AP Scanner analysis of {}", escape(&reading.local));
+
+        writeln!(output, "<!DOCTYPE html>")?;
+        writeln!(output, "<html>\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(output, "<title>{}</title>", title)?;
+        writeln!(output, "<style>{}</style>\n</head>\n<body>", STYLE)?;
+        writeln!(output, "<h1>{}</h1>", title)?;
+        writeln!(
+            output,
+            "<p>Regulatory domain {} ({}), seed {}, {} networks heard.</p>",
+            escape(domain.name),
+            escape(reading.country.as_deref().unwrap_or("unknown country")),
+            reading.seed,
+            networks(reading).count()
+        )?;
+
+        for chart in [Band::Ghz2_4, Band::Ghz5, Band::Ghz6] {
+            let pairs = networks(reading)
+                .filter(|(wifi, _)| wifi.band() == chart)
+                .collect::<Vec<_>>();
+
+            if !pairs.is_empty() {
+                write_chart(&mut output, chart, domain, &pairs)?;
+            }
+        }
+
+        writeln!(output, "<h2>Networks</h2>\n<table>")?;
+        writeln!(
+            output,
+            "<tr>{}</tr>",
+            COLUMNS
+                .iter()
+                .map(|column| format!("<th>{}</th>", column))
+                .collect::<String>()
+        )?;
+        for (wifi, suggestion) in networks(reading) {
+            writeln!(
+                output,
+                "<tr>{}</tr>",
+                row(reading, wifi, suggestion)
+                    .iter()
+                    .map(|value| format!("<td>{}</td>", escape(value)))
+                    .collect::<String>()
+            )?;
+        }
+        writeln!(output, "</table>")?;
+
+        // the interference, confidence and ties behind every suggestion
+        if !reading.rationale.is_empty() {
+            writeln!(output, "<h2>Why</h2>\n<ul>")?;
+
+            for (wifi, _) in networks(reading) {
+                if let Some(rationale) = reading.rationale.get(&wifi.mac) {
+                    writeln!(
+                        output,
+                        "<li>{} ({}): {}</li>",
+                        escape(&wifi.ssid),
+                        escape(&wifi.mac),
+                        escape(&rationale.to_string()).replace('\n', "<br>")
+                    )?;
+                }
+            }
+
+            writeln!(output, "</ul>")?;
+        }
+
+        if reading.width_2_4_ghz.is_some()
+            || reading.width_5_ghz.is_some()
+            || reading.width_6_ghz.is_some()
+        {
+            writeln!(output, "<h2>Widths</h2>")?;
+            write_width(&mut output, "2.4GHz", reading.width_2_4_ghz.as_ref())?;
+            write_width(&mut output, "5GHz", reading.width_5_ghz.as_ref())?;
+            write_width(&mut output, "6GHz", reading.width_6_ghz.as_ref())?;
+        }
+
+        if !reading.power.is_empty() {
+            writeln!(output, "<h2>Transmit power</h2>\n<ul>")?;
+
+            for (wifi, _) in networks(reading) {
+                if let Some(power) = reading.power.get(&wifi.mac) {
+                    writeln!(
+                        output,
+                        "<li>{} ({}): {}</li>",
+                        escape(&wifi.ssid),
+                        escape(&wifi.mac),
+                        escape(&power.to_string()).replace('\n', "<br>")
+                    )?;
+                }
+            }
+
+            writeln!(output, "</ul>")?;
+        }
+
+        writeln!(output, "</body>\n</html>")?;
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::reading;
+    use super::*;
+
+    #[test]
+    fn every_field_is_escaped() {
+        let output = Html.render(&reading()).unwrap();
+
+        assert!(output.contains("<h1>AP Scanner analysis of Main | Lab</h1>"));
+        assert!(output.contains("<td>Lab &lt;a|b&gt;, c</td>"));
+        assert!(output.contains("<li>Lab &lt;a|b&gt;, c (3c:37:86:1a:2b:3d): Interference now:"));
+        assert!(!output.contains("Lab <a"));
+    }
+}
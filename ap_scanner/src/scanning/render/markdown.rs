@@ -0,0 +1,134 @@
+use super::{band_name, dbm, networks, suggested_label, Renderer};
+use crate::scanning::reading::{Reading, Suggestion, WidthAdvice};
+use crate::scanning::regulatory::Domain;
+use std::fmt::Write;
+
+/// A summary of the suggestions, to paste in a ticket or a wiki page.
+pub struct Markdown;
+
+/// `text` as the content of a table cell, or of any other single line.
+fn cell(text: &str) -> String {
+    // `<` would open an HTML tag or an autolink
+    text.replace('|', "\\|")
+        .replace('<', "\\<")
+        .replace(['\r', '\n'], " ")
+}
+
+fn write_width(output: &mut String, band: &str, advice: Option<&WidthAdvice>) -> std::fmt::Result {
+    // the first line is the advice, the others why
+    if let Some(line) =
+        advice.and_then(|advice| advice.to_string().lines().next().map(String::from))
+    {
+        writeln!(output, "- {}: {}", band, line)?;
+    }
+
+    Ok(())
+}
+
+impl Renderer for Markdown {
+    fn render(&self, reading: &Reading) -> anyhow::Result<String> {
+        let mut output = String::new();
+        let domain = Domain::resolve(reading.country.as_deref());
+
+        let total = networks(reading).count();
+        let planned = networks(reading)
+            .filter(|(_, suggestion)| !matches!(suggestion, Suggestion::Fixed))
+            .collect::<Vec<_>>();
+
+        writeln!(output, "# AP Scanner analysis of {}", cell(&reading.local))?;
+        writeln!(output)?;
+        writeln!(
+            output,
+            "Regulatory domain {} ({}), seed {}.",
+            domain.name,
+            reading.country.as_deref().unwrap_or("unknown country"),
+            reading.seed
+        )?;
+        writeln!(
+            output,
+            "{} networks heard, {} of them planned.",
+            total,
+            planned.len()
+        )?;
+
+        writeln!(output)?;
+        writeln!(output, "## Suggestions")?;
+        writeln!(output)?;
+        writeln!(
+            output,
+            "| Band | SSID | MAC | Channel | Suggested | Interference now | After | Confidence |"
+        )?;
+        writeln!(output, "|---|---|---|---:|---|---:|---:|---:|")?;
+
+        for (wifi, suggestion) in planned {
+            let rationale = reading.rationale.get(&wifi.mac);
+
+            writeln!(
+                output,
+                "| {} | {} | {} | {} | {} | {} | {} | {} |",
+                band_name(wifi.band()),
+                cell(&wifi.ssid),
+                wifi.mac,
+                wifi.channel,
+                suggested_label(wifi, suggestion),
+                dbm(rationale.and_then(|rationale| rationale.current)),
+                dbm(rationale.and_then(|rationale| rationale.suggested)),
+                match rationale {
+                    Some(rationale) if !rationale.tied.is_empty() => "tied".to_string(),
+                    Some(rationale) => format!("{:.0}%", rationale.confidence * 100.0),
+                    None => "none".to_string(),
+                }
+            )?;
+        }
+
+        if reading.width_2_4_ghz.is_some()
+            || reading.width_5_ghz.is_some()
+            || reading.width_6_ghz.is_some()
+        {
+            writeln!(output)?;
+            writeln!(output, "## Widths")?;
+            writeln!(output)?;
+            write_width(&mut output, "2.4GHz", reading.width_2_4_ghz.as_ref())?;
+            write_width(&mut output, "5GHz", reading.width_5_ghz.as_ref())?;
+            write_width(&mut output, "6GHz", reading.width_6_ghz.as_ref())?;
+        }
+
+        if !reading.power.is_empty() {
+            writeln!(output)?;
+            writeln!(output, "## Transmit power")?;
+            writeln!(output)?;
+
+            for (wifi, _) in networks(reading) {
+                if let Some(power) = reading.power.get(&wifi.mac) {
+                    let advice = power.to_string();
+
+                    writeln!(
+                        output,
+                        "- {} ({}): {}",
+                        cell(&wifi.ssid),
+                        wifi.mac,
+                        advice.lines().collect::<Vec<_>>().join(" ")
+                    )?;
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::reading;
+    use super::*;
+
+    #[test]
+    fn cells_and_lines_are_escaped() {
+        let output = Markdown.render(&reading()).unwrap();
+
+        assert!(output.starts_with("# AP Scanner analysis of Main \\| Lab\n"));
+        assert!(output.contains("| 2.4GHz | Lab \\<a\\|b>, c | 3c:37:86:1a:2b:3d | 6 |"));
+        assert!(output.contains("- Lab \\<a\\|b>, c (3c:37:86:1a:2b:3d): "));
+        assert!(!output.contains("Lab <a"));
+    }
+}
@@ -0,0 +1,273 @@
+// Renderers, the analysis of a reading in the format it is needed in.
+//
+// The text is what the scanner always printed. The table, the CSV and the Markdown list every
+// BSS on a row of its own, with its suggestion at the width it uses today. The HTML report is a
+// single file with the channel charts drawn in, to be sent as it is. The chart draws them in text
+// for a terminal.
+mod chart;
+mod csv;
+mod html;
+mod markdown;
+mod table;
+mod text;
+
+pub use self::csv::Csv;
+pub use chart::Chart;
+pub use html::Html;
+pub use markdown::Markdown;
+pub use table::Table;
+pub use text::Text;
+
+use super::reading::{Reading, Suggestion};
+use super::regulatory::Band;
+use super::wifi::Wifi;
+use std::fmt::Display;
+
+/// Turns the analysis of a reading into text.
+pub trait Renderer {
+    fn render(&self, reading: &Reading) -> anyhow::Result<String>;
+}
+
+/// The formats to pick from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum Format {
+    /// free form text
+    #[default]
+    Text,
+    /// an aligned table, one BSS per row
+    Table,
+    /// the reading itself
+    Json,
+    /// one row per BSS
+    Csv,
+    /// a summary of the suggestions
+    Markdown,
+    /// a report in a single file, with channel charts
+    Html,
+    /// the channels the networks occupy, drawn in text
+    Chart,
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Text => "text",
+            Self::Table => "table",
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Markdown => "markdown",
+            Self::Html => "html",
+            Self::Chart => "chart",
+        })
+    }
+}
+
+impl Format {
+    pub fn renderer(&self) -> Box<dyn Renderer> {
+        match self {
+            Self::Text => Box::new(Text),
+            Self::Table => Box::new(Table),
+            Self::Json => Box::new(Json),
+            Self::Csv => Box::new(Csv),
+            Self::Markdown => Box::new(Markdown),
+            Self::Html => Box::new(Html),
+            Self::Chart => Box::<Chart>::default(),
+        }
+    }
+}
+
+pub struct Json;
+
+impl Renderer for Json {
+    fn render(&self, reading: &Reading) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(reading)?)
+    }
+}
+
+/// Every BSS of a reading along with its suggestion, 2.4, 5 and 6 GHz in that order.
+fn networks(reading: &Reading) -> impl Iterator<Item = &(Wifi, Suggestion)> {
+    reading
+        .wifi_2_4_ghz
+        .values()
+        .chain(reading.wifi_5_ghz.values())
+        .chain(reading.wifi_6_ghz.values())
+        .flatten()
+}
+
+fn band_name(band: Band) -> &'static str {
+    match band {
+        Band::Ghz2_4 => "2.4GHz",
+        Band::Ghz5 => "5GHz",
+        Band::Ghz6 => "6GHz",
+    }
+}
+
+/// Center frequency in MHz of a channel of `band`.
+fn channel_mhz(band: Band, channel: u8) -> f32 {
+    match band {
+        Band::Ghz2_4 if channel == 14 => 2484.0,
+        Band::Ghz2_4 => 2407.0 + 5.0 * channel as f32,
+        Band::Ghz5 => 5000.0 + 5.0 * channel as f32,
+        Band::Ghz6 => 5950.0 + 5.0 * channel as f32,
+    }
+}
+
+/// How wide the spectrum of `wifi` is in MHz, a 20 MHz 2.4 GHz transmission spreads over 22.
+fn span_mhz(wifi: &Wifi) -> f32 {
+    match (wifi.is_2_4g(), wifi.width.mhz()) {
+        (true, 20) => 22.0,
+        (_, mhz) => mhz as f32,
+    }
+}
+
+/// Center frequency in MHz of where `wifi` is today.
+fn center_mhz(wifi: &Wifi) -> f32 {
+    channel_mhz(wifi.band(), wifi.center_channel.unwrap_or(wifi.channel))
+}
+
+/// The center channel suggested at the width `wifi` uses today and whether it is a DFS one,
+/// `None` for networks left where they are and widths without a block.
+fn suggested(wifi: &Wifi, suggestion: &Suggestion) -> Option<(u8, bool)> {
+    let center = match suggestion {
+        Suggestion::Fixed => return None,
+        // a 40 MHz network keeps its secondary channel wherever there is room for it
+        Suggestion::Suggestion2g(channel) => match wifi.width.mhz() {
+            20 => (*channel, false),
+            _ if *channel <= 7 => (channel + 2, false),
+            _ => (channel - 2, false),
+        },
+        // without DFS channels when there is a block
+        Suggestion::Suggestion5g(sug) => {
+            let (ndfs, dfs) = match wifi.width.mhz() {
+                20 => (sug.ndfs_20, sug.dfs_20),
+                40 => (sug.ndfs_40, sug.dfs_40),
+                80 => (sug.ndfs_80, sug.dfs_80),
+                _ => (0, sug.dfs_160),
+            };
+
+            match ndfs {
+                0 => (dfs, true),
+                _ => (ndfs, false),
+            }
+        }
+        Suggestion::Suggestion6g(sug) => match wifi.width.mhz() {
+            20 => (sug.psc_20, false),
+            40 => (sug.psc_40, false),
+            80 => (sug.psc_80, false),
+            160 => (sug.psc_160, false),
+            _ => (sug.psc_320, false),
+        },
+    };
+
+    match center.0 {
+        0 => None,
+        _ => Some(center),
+    }
+}
+
+/// The suggestion for `wifi` in a few words.
+fn suggested_label(wifi: &Wifi, suggestion: &Suggestion) -> String {
+    match (suggestion, suggested(wifi, suggestion)) {
+        (Suggestion::Fixed, _) => "left as is".to_string(),
+        (_, None) => "none".to_string(),
+        (Suggestion::Suggestion2g(channel), _) => channel.to_string(),
+        (_, Some((center, true))) => format!("{} DFS", center),
+        (_, Some((center, false))) => center.to_string(),
+    }
+}
+
+fn dbm(dbm: Option<f32>) -> String {
+    match dbm {
+        Some(dbm) => format!("{:.1}", dbm),
+        None => "none".to_string(),
+    }
+}
+
+/// The columns of a BSS in tables and CSV rows.
+const COLUMNS: [&str; 11] = [
+    "band",
+    "ssid",
+    "mac",
+    "channel",
+    "width",
+    "signal",
+    "security",
+    "suggested",
+    "interference",
+    "after",
+    "power",
+];
+
+/// A BSS of `reading` as the values of `COLUMNS`.
+fn row(reading: &Reading, wifi: &Wifi, suggestion: &Suggestion) -> [String; 11] {
+    let rationale = reading.rationale.get(&wifi.mac);
+    let power = reading.power.get(&wifi.mac);
+
+    [
+        band_name(wifi.band()).to_string(),
+        wifi.ssid.clone(),
+        wifi.mac.clone(),
+        wifi.channel.to_string(),
+        wifi.width.mhz().to_string(),
+        format!("{:.0}", wifi.signal),
+        wifi.security
+            .as_ref()
+            .map(|security| format!("{:?}", security.auth))
+            .unwrap_or_else(|| "unknown".to_string()),
+        suggested_label(wifi, suggestion),
+        dbm(rationale.and_then(|rationale| rationale.current)),
+        dbm(rationale.and_then(|rationale| rationale.suggested)),
+        power
+            .map(|power| power.suggested.to_string())
+            .unwrap_or_default(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanning::{fleet::Fleet, iw, planner::Greedy};
+    use std::collections::BTreeMap;
+
+    // an SSID every format has to escape in its own way
+    pub(super) const SSID: &str = "Lab <a|b>, c";
+
+    /// The iwlwifi scan planned with a fixed seed, the 2.4 GHz Office renamed to `SSID`.
+    pub(super) fn reading() -> Reading {
+        let networks = iw::parse_scan(include_str!("../../../fixtures/iw/iwlwifi.txt"))
+            .networks
+            .into_iter()
+            .map(|mut wifi| {
+                if wifi.mac == "3c:37:86:1a:2b:3d" {
+                    wifi.ssid = SSID.to_string();
+                }
+
+                let signal = wifi.signal;
+                (wifi, vec![Some(signal)])
+            })
+            .collect();
+
+        let mut reading = Reading::plan(
+            "Main | Lab".to_string(),
+            networks,
+            None,
+            BTreeMap::new(),
+            &Fleet::default(),
+            &Greedy,
+            7,
+        )
+        .unwrap();
+        reading.timestamp = 1_650_000_000_000;
+
+        reading
+    }
+
+    #[test]
+    fn json_is_the_reading() {
+        let output = Json.render(&reading()).unwrap();
+        let parsed = serde_json::from_str::<Reading>(&output).unwrap();
+
+        assert_eq!(parsed.wifi_2_4_ghz[&6][0].0.ssid, SSID);
+        assert_eq!(output, serde_json::to_string_pretty(&reading()).unwrap());
+    }
+}
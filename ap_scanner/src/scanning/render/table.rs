@@ -0,0 +1,59 @@
+use super::{networks, row, Renderer, COLUMNS};
+use crate::scanning::reading::Reading;
+use std::fmt::Write;
+
+/// Every BSS on a row, columns padded to line up in a terminal.
+pub struct Table;
+
+impl Renderer for Table {
+    fn render(&self, reading: &Reading) -> anyhow::Result<String> {
+        let header = COLUMNS.map(str::to_uppercase);
+        let rows = networks(reading)
+            .map(|(wifi, suggestion)| row(reading, wifi, suggestion))
+            .collect::<Vec<_>>();
+
+        let mut widths = header.clone().map(|column| column.chars().count());
+        for row in &rows {
+            for (width, value) in widths.iter_mut().zip(row) {
+                *width = (*width).max(value.chars().count());
+            }
+        }
+
+        let mut output = String::new();
+
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(value, width)| {
+                    let padding = width - value.chars().count();
+                    format!("{}{}", value, " ".repeat(padding))
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+
+            writeln!(output, "{}", line.trim_end())?;
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{reading, SSID};
+    use super::*;
+
+    #[test]
+    fn columns_line_up() {
+        let output = Table.render(&reading()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("BAND    SSID"));
+        // the SSID is printed as it is, the MAC column starts at the same place on every row
+        let mac = lines[0].find("MAC").unwrap();
+        assert_eq!(&lines[1][..mac], format!("2.4GHz  {:14}", SSID));
+        assert!(lines[1][mac..].starts_with("3c:37:86:1a:2b:3d"));
+    }
+}
@@ -0,0 +1,219 @@
+use super::Renderer;
+use crate::scanning::reading::{Reading, Suggestion, WidthAdvice};
+use crate::scanning::regulatory::{Band, Domain};
+use itertools::Itertools;
+use std::fmt::Write;
+
+/// The analysis as free form text, one section after another.
+pub struct Text;
+
+fn write_indented(output: &mut String, text: &str) -> std::fmt::Result {
+    for line in text.lines() {
+        writeln!(output, "\t{}", line)?;
+    }
+
+    Ok(())
+}
+
+fn write_width(output: &mut String, band: &str, advice: Option<&WidthAdvice>) -> std::fmt::Result {
+    if let Some(advice) = advice {
+        writeln!(output, "Recommended width for Wifi {} networks.", band)?;
+        write_indented(output, &advice.to_string())?;
+    }
+
+    Ok(())
+}
+
+fn write_rationale(output: &mut String, reading: &Reading, mac: &str) -> std::fmt::Result {
+    match reading.rationale.get(mac) {
+        Some(rationale) => write_indented(output, &rationale.to_string()),
+        None => Ok(()),
+    }
+}
+
+impl Renderer for Text {
+    fn render(&self, reading: &Reading) -> anyhow::Result<String> {
+        let mut output = String::new();
+
+        let count_2g = reading
+            .wifi_2_4_ghz
+            .values()
+            .map(|v| v.len())
+            .sum::<usize>();
+        let count_5g = reading.wifi_5_ghz.values().map(|v| v.len()).sum::<usize>();
+        let count_6g = reading.wifi_6_ghz.values().map(|v| v.len()).sum::<usize>();
+
+        writeln!(
+            output,
+            "AP Scanner 2022\nNumber of 2.4GHz networks: {}\nNumber of 5GHz networks: {}\nNumber of 6GHz networks: {}",
+            count_2g, count_5g, count_6g
+        )?;
+
+        let domain = Domain::resolve(reading.country.as_deref());
+
+        writeln!(
+            output,
+            "Regulatory domain: {} ({}).",
+            domain.name,
+            reading.country.as_deref().unwrap_or("unknown country")
+        )?;
+        writeln!(output, "Seed: {}.", reading.seed)?;
+
+        let fixed = reading
+            .wifi_2_4_ghz
+            .values()
+            .chain(reading.wifi_5_ghz.values())
+            .chain(reading.wifi_6_ghz.values())
+            .flatten()
+            .filter(|pair| matches!(pair.1, Suggestion::Fixed))
+            .count();
+
+        if fixed > 0 {
+            writeln!(
+                output,
+                "Planning {} of our networks, {} others are left where they are.",
+                count_2g + count_5g + count_6g - fixed,
+                fixed
+            )?;
+        }
+
+        writeln!(output, "Interference per Wifi 2.4GHz channel.")?;
+        for channel in domain.channels(Band::Ghz2_4) {
+            match reading.interference_2_4_ghz.get(&channel) {
+                Some(dbm) => writeln!(output, "\t{}: {:.1} dBm", channel, dbm)?,
+                None => writeln!(output, "\t{}: none", channel)?,
+            }
+        }
+
+        writeln!(output, "Suggestions for Wifi 2.4GHz networks.")?;
+        // print the suggestions only, networks already on their best channel are left out
+        for pair in reading.wifi_2_4_ghz.values().flatten() {
+            if matches!(pair.1, Suggestion::Suggestion2g(channel) if channel != pair.0.channel) {
+                writeln!(
+                    output,
+                    "Wifi network with SSID and MAC: {}, {}.\n\tCurrent channel: {}.\n\tSuggested change:{:?}",
+                    pair.0.ssid, pair.0.mac, pair.0.channel, pair.1
+                )?;
+                write_rationale(&mut output, reading, &pair.0.mac)?;
+            }
+        }
+
+        write_width(&mut output, "2.4GHz", reading.width_2_4_ghz.as_ref())?;
+
+        writeln!(
+            output,
+            "The suggested distributions for Wifi 5GHz networks."
+        )?;
+
+        for pair in reading
+            .wifi_5_ghz
+            .values()
+            .flatten()
+            .filter(|pair| !matches!(pair.1, Suggestion::Fixed))
+        {
+            writeln!(
+                output,
+                "Wifi network with SSID and MAC: {}, {}.\n\tSuggested Channels per channel width:",
+                pair.0.ssid, pair.0.mac
+            )?;
+            if let Suggestion::Suggestion5g(sug) = &pair.1 {
+                let block = |center: u8, mhz: u16| match domain.max_eirp(Band::Ghz5, center, mhz) {
+                    Some(eirp) => format!("{} (up to {} dBm)", center, eirp),
+                    None => "none".to_string(),
+                };
+
+                writeln!(output, "\t\t20MHz: {}", block(sug.ndfs_20, 20))?;
+                writeln!(output, "\t\tDFS 20MHz: {}", block(sug.dfs_20, 20))?;
+
+                writeln!(output, "\t\t40MHz: {}", block(sug.ndfs_40, 40))?;
+                writeln!(output, "\t\tDFS 40MHz: {}", block(sug.dfs_40, 40))?;
+
+                writeln!(output, "\t\t80MHz: {}", block(sug.ndfs_80, 80))?;
+                writeln!(output, "\t\tDFS 80MHz: {}", block(sug.dfs_80, 80))?;
+
+                writeln!(output, "\t\tDFS 160MHz: {}", block(sug.dfs_160, 160))?;
+            }
+            write_rationale(&mut output, reading, &pair.0.mac)?;
+        }
+
+        write_width(&mut output, "5GHz", reading.width_5_ghz.as_ref())?;
+
+        writeln!(
+            output,
+            "The suggested distributions for Wifi 6GHz networks."
+        )?;
+
+        for pair in reading
+            .wifi_6_ghz
+            .values()
+            .flatten()
+            .filter(|pair| !matches!(pair.1, Suggestion::Fixed))
+        {
+            writeln!(output, "Wifi network with SSID and MAC: {}, {}.\n\tSuggested PSC channels per channel width:", pair.0.ssid, pair.0.mac)?;
+            if let Suggestion::Suggestion6g(sug) = &pair.1 {
+                writeln!(output, "\t\t20MHz: {}", sug.psc_20)?;
+                writeln!(output, "\t\t40MHz: {}", sug.psc_40)?;
+                writeln!(output, "\t\t80MHz: {}", sug.psc_80)?;
+                writeln!(output, "\t\t160MHz: {}", sug.psc_160)?;
+                writeln!(output, "\t\t320MHz: {}", sug.psc_320)?;
+            }
+            if !pair.0.is_psc() {
+                writeln!(
+                    output,
+                    "\tIts primary channel {} is not a PSC, clients that only scan PSCs miss it.",
+                    pair.0.channel
+                )?;
+            }
+            write_rationale(&mut output, reading, &pair.0.mac)?;
+        }
+
+        write_width(&mut output, "6GHz", reading.width_6_ghz.as_ref())?;
+
+        if !reading.power.is_empty() {
+            writeln!(output, "Transmit power advice.")?;
+
+            for (wifi, _) in reading
+                .wifi_2_4_ghz
+                .values()
+                .chain(reading.wifi_5_ghz.values())
+                .chain(reading.wifi_6_ghz.values())
+                .flatten()
+            {
+                if let Some(power) = reading.power.get(&wifi.mac) {
+                    writeln!(
+                        output,
+                        "Wifi network with SSID and MAC: {}, {}.",
+                        wifi.ssid, wifi.mac
+                    )?;
+                    write_indented(&mut output, &power.to_string())?;
+                }
+            }
+        }
+
+        if !reading.survey.is_empty() {
+            writeln!(output, "Channel survey.")?;
+
+            for (frequency, survey) in reading.survey.iter().sorted_by_key(|x| x.0) {
+                let busy = survey
+                    .busy_ratio()
+                    .map(|ratio| format!("{:.0}%", ratio * 100.0))
+                    .unwrap_or_else(|| "unknown".into());
+                let noise = survey
+                    .noise
+                    .map(|noise| format!("{} dBm", noise))
+                    .unwrap_or_else(|| "unknown".into());
+
+                writeln!(
+                    output,
+                    "\t{} MHz{}: busy {}, noise {}",
+                    frequency,
+                    if survey.in_use { " (in use)" } else { "" },
+                    busy,
+                    noise
+                )?;
+            }
+        }
+
+        Ok(output)
+    }
+}
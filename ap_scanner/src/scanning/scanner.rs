@@ -1,13 +1,37 @@
 use anyhow::{Context, Result};
-use rayon::iter::{ParallelBridge, ParallelIterator};
-use std::{process::Command, vec};
+use std::process::Command;
 
-use super::wifi::Wifi;
+use super::{
+    iw::{self, ParsedScan},
+    nl80211,
+    source::ScanSource,
+    survey::{self, ChannelSurvey},
+};
 
+/// Live scans through nl80211.
 pub struct Scanner;
 
-impl Scanner {
-    pub fn scan() -> Result<Vec<Wifi>> {
+/// Live scans through the `iw` binary only.
+pub struct IwScanner;
+
+impl ScanSource for Scanner {
+    fn scan(&self) -> Result<ParsedScan> {
+        nl80211::scan()
+            .map(|(networks, warnings)| ParsedScan { networks, warnings })
+            .with_context(|| {
+                anyhow::anyhow!(
+                    "Failed to scan through nl80211, `--iw` scans through the `iw` binary instead."
+                )
+            })
+    }
+
+    fn survey(&self) -> Result<Vec<ChannelSurvey>> {
+        nl80211::survey()
+    }
+}
+
+impl ScanSource for IwScanner {
+    fn scan(&self) -> Result<ParsedScan> {
         // iw dev wlp3s0 scan
         let ch = Command::new("iw")
             .args(["dev", &Self::get_interface()?, "scan"])
@@ -15,19 +39,26 @@ impl Scanner {
             .map(|out| String::from_utf8(out.stdout))
             .with_context(|| anyhow::anyhow!("No output from \"iw dev iface scan\""))??;
 
-        let patterns = &["\tBSS", " BSS "];
-        let replaces = &["\tbss", " bss "];
-
-        let mut out = vec![];
-        let ac = aho_corasick::AhoCorasick::new(patterns);
-        ac.stream_replace_all(ch.as_bytes(), &mut out, replaces)?;
+        Ok(Self::parse(&ch))
+    }
 
-        let output = String::from_utf8(out)?;
-        let mut clean = output.split("BSS ");
+    fn survey(&self) -> Result<Vec<ChannelSurvey>> {
+        // iw dev wlp3s0 survey dump
+        let ch = Command::new("iw")
+            .args(["dev", &Self::get_interface()?, "survey", "dump"])
+            .output()
+            .map(|out| String::from_utf8(out.stdout))
+            .with_context(|| anyhow::anyhow!("No output from \"iw dev iface survey dump\""))??;
 
-        clean.next();
+        Ok(survey::parse_iw_survey(&ch))
+    }
+}
 
-        Ok(clean.par_bridge().flat_map(str::parse).collect::<Vec<_>>())
+impl IwScanner {
+    /// Parses the text printed by `iw dev <iface> scan`.
+    /// Networks that had to be dropped, and why, are in the warnings.
+    pub fn parse(ch: &str) -> ParsedScan {
+        iw::parse_scan(ch)
     }
 
     fn get_interface() -> Result<String> {
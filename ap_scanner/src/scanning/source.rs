@@ -0,0 +1,255 @@
+use super::{
+    iw::ParsedScan,
+    measure::Measure,
+    regulatory,
+    scanner::IwScanner,
+    survey::{self, ChannelSurvey},
+    wifi::{Frequency, Width, Wifi},
+};
+use anyhow::{anyhow, Context, Result};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::path::PathBuf;
+
+/// Anything that can produce a list of observed networks.
+/// `Reading` is built from a source, so the analysis doesn't need a wireless interface.
+pub trait ScanSource {
+    /// The networks heard, and what was odd about the ones that had to be dropped or guessed.
+    fn scan(&self) -> Result<ParsedScan>;
+
+    /// Per channel survey data, sources without any return an empty list.
+    fn survey(&self) -> Result<Vec<ChannelSurvey>> {
+        Ok(vec![])
+    }
+}
+
+/// Takes a measure from `source`.
+pub fn measure(local: String, source: &dyn ScanSource) -> Result<Measure> {
+    let scan = source.scan()?;
+    let mut warnings = scan
+        .warnings
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    // not every driver keeps survey data, the measure is still useful without it
+    let survey = source.survey().unwrap_or_else(|err| {
+        warnings.push(format!("No survey data: {:#}", err));
+        vec![]
+    });
+
+    Measure::new(local, scan.networks, survey, warnings)
+}
+
+/// Replays a capture from disk, either the text output of `iw dev <iface> scan`
+/// or a JSON list of `Wifi`.
+/// The survey, if any, comes from the text output of `iw dev <iface> survey dump`
+/// or a JSON list of `ChannelSurvey`.
+pub struct ReplaySource {
+    pub path: PathBuf,
+    pub survey: Option<PathBuf>,
+}
+
+impl ReplaySource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            survey: None,
+        }
+    }
+
+    pub fn with_survey(mut self, path: impl Into<PathBuf>) -> Self {
+        self.survey = Some(path.into());
+        self
+    }
+}
+
+impl ScanSource for ReplaySource {
+    fn scan(&self) -> Result<ParsedScan> {
+        let data = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to open capture {:?}", self.path))?;
+
+        if data.trim_start().starts_with('[') {
+            serde_json::from_str::<Vec<Wifi>>(&data)
+                .map(ParsedScan::from)
+                .with_context(|| format!("Failed to load networks from {:?}", self.path))
+        } else {
+            Ok(IwScanner::parse(&data))
+        }
+    }
+
+    fn survey(&self) -> Result<Vec<ChannelSurvey>> {
+        let path = match &self.survey {
+            Some(path) => path,
+            None => return Ok(vec![]),
+        };
+
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to open survey {:?}", path))?;
+
+        if data.trim_start().starts_with('[') {
+            serde_json::from_str(&data)
+                .with_context(|| format!("Failed to load survey from {:?}", path))
+        } else {
+            Ok(survey::parse_iw_survey(&data))
+        }
+    }
+}
+
+const CHANNELS_2G: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+const CHANNELS_5G: &[u8] = &[
+    36, 40, 44, 48, 52, 56, 60, 64, 100, 104, 108, 112, 116, 120, 124, 128, 132, 136, 140, 144,
+    149, 153, 157, 161, 165,
+];
+
+/// Generates a random environment, useful on machines without wireless hardware.
+/// The same seed generates the same networks.
+pub struct SyntheticSource {
+    // number of networks to generate
+    pub count: usize,
+    pub seed: u64,
+}
+
+impl SyntheticSource {
+    pub fn new(count: usize, seed: u64) -> Self {
+        Self { count, seed }
+    }
+}
+
+impl ScanSource for SyntheticSource {
+    fn scan(&self) -> Result<ParsedScan> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+
+        (0..self.count)
+            .map(|i| {
+                // channel list, number of widths available and base frequency of each band
+                let (channels, widths, base) = match rng.gen_range(0..3) {
+                    0 => (CHANNELS_2G.to_vec(), 2, 2407),
+                    1 => (CHANNELS_5G.to_vec(), 4, 5000),
+                    _ => ((1..=233).step_by(4).collect(), 5, 5950),
+                };
+
+                let channel = *channels
+                    .choose(&mut rng)
+                    .with_context(|| anyhow!("No channels to pick from."))?;
+
+                // 80 and 160 MHz only exist from 5 GHz up, 320 MHz only on 6 GHz, and a channel
+                // without a block of the width drawn gets the next narrower one
+                let (width, center) = (0..=rng.gen_range(0..widths))
+                    .rev()
+                    .map(|index| match index {
+                        0 => Width::MHz20,
+                        1 => Width::MHz40,
+                        2 => Width::MHz80,
+                        3 => Width::MHz160,
+                        _ => Width::MHz320,
+                    })
+                    .find_map(|width| {
+                        block_center(channel, width.mhz(), base).map(|center| (width, center))
+                    })
+                    .with_context(|| anyhow!("No block holds channel {}.", channel))?;
+
+                // locally administered unicast addresses
+                let mut mac = [0u8; 6];
+                rng.fill(&mut mac);
+                mac[0] = (mac[0] & 0xfc) | 0x02;
+
+                Ok(Wifi {
+                    ssid: format!("synthetic-{}", i),
+                    mac: mac
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<Vec<_>>()
+                        .join(":"),
+                    channel,
+                    signal: rng.gen_range(-90.0..-30.0),
+                    frequency: Frequency::from(match channel {
+                        14 if base == 2407 => 2484,
+                        _ => base + 5 * channel as u16,
+                    }),
+                    center_channel: Some(center),
+                    center_channel_2: None,
+                    width,
+                    security: None,
+                    bss_load: None,
+                    country: None,
+                    beacon_interval: None,
+                    capability: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(ParsedScan::from)
+    }
+}
+
+/// Center channel of the aligned block of `mhz` containing `channel`, `None` when there is none.
+fn block_center(channel: u8, mhz: u16, base_frequency: u16) -> Option<u8> {
+    // channel numbers a block spans, and how far its center is from its first channel
+    let span = (mhz / 5) as u8;
+    let offset = span.saturating_sub(4) / 2;
+
+    match base_frequency {
+        _ if mhz == 20 => Some(channel),
+        // 2.4 GHz 40 MHz, the secondary channel goes wherever there is room for it
+        2407 if channel <= 7 => Some(channel + 2),
+        2407 => Some(channel - 2),
+        // 6 GHz blocks are aligned on channel 1, the last channel is 233
+        5950 => Some(1 + (channel - 1) / span * span + offset)
+            .filter(|center| *center as u16 + offset as u16 <= 233),
+        _ => regulatory::block_5g(channel, mhz),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanning::{fleet::Fleet, planner::Greedy};
+
+    #[test]
+    fn replayed_scan_plans_as_checked_in() {
+        let source = ReplaySource::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/iw/iwlwifi.txt"
+        ));
+        let mut measure = measure("Office".to_string(), &source).unwrap();
+        measure.timestamp = 1_650_000_000_000;
+
+        let reading = measure
+            .analyse(None, &Fleet::default(), &Greedy, 7)
+            .unwrap();
+        let json = serde_json::to_string_pretty(&reading).unwrap();
+
+        assert_eq!(json, include_str!("../../fixtures/golden/iwlwifi.json"));
+    }
+
+    #[test]
+    fn same_seed_same_networks() {
+        let json = |seed| {
+            serde_json::to_string(&SyntheticSource::new(50, seed).scan().unwrap().networks).unwrap()
+        };
+
+        assert_eq!(json(7), json(7));
+        assert_ne!(json(7), json(8));
+    }
+
+    #[test]
+    fn synthetic_5g_networks_sit_on_blocks() {
+        let networks = SyntheticSource::new(500, 7).scan().unwrap().networks;
+        let five = networks
+            .iter()
+            .filter(|wifi| wifi.is_5g())
+            .collect::<Vec<_>>();
+
+        assert!(five.iter().any(|wifi| wifi.width.mhz() > 40));
+        for wifi in five {
+            assert_eq!(
+                wifi.center_channel,
+                regulatory::block_5g(wifi.channel, wifi.width.mhz()),
+                "{} on {} at {} MHz",
+                wifi.ssid,
+                wifi.channel,
+                wifi.width.mhz()
+            );
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// Channel survey data as printed by `iw dev <iface> survey dump`.
+//
+// Each channel is a `Survey data from <iface>` line followed by tab indented `key: value` lines.
+// Drivers report different subsets of the fields, all but the frequency are optional.
+pub use ap_scanner_core::survey::ChannelSurvey;
+
+/// Parses the text printed by `iw dev <iface> survey dump`, entries without a frequency are skipped.
+pub fn parse_iw_survey(output: &str) -> Vec<ChannelSurvey> {
+    output
+        .split("Survey data from")
+        .skip(1)
+        .filter_map(|block| {
+            let mut survey = ChannelSurvey::default();
+
+            for (key, value) in block
+                .lines()
+                .skip(1)
+                .filter_map(|line| line.split_once(':'))
+            {
+                // e.g. "5180 MHz [in use]" or "1234 ms"
+                let value = value.trim();
+                let number = value.split_whitespace().next().unwrap_or_default();
+
+                match key.trim() {
+                    "frequency" => {
+                        survey.frequency = number.parse::<f32>().ok()?.round() as u16;
+                        survey.in_use = value.ends_with("[in use]");
+                    }
+                    "noise" => survey.noise = number.parse().ok(),
+                    "channel active time" => survey.active_time = number.parse().ok(),
+                    "channel busy time" => survey.busy_time = number.parse().ok(),
+                    "channel receive time" => survey.receive_time = number.parse().ok(),
+                    "channel transmit time" => survey.transmit_time = number.parse().ok(),
+                    _ => {}
+                }
+            }
+
+            Some(survey).filter(|survey| survey.frequency != 0)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ath9k_survey() {
+        let survey = parse_iw_survey(include_str!("../../fixtures/iw/survey/ath9k.txt"));
+        assert_eq!(survey.len(), 13);
+
+        assert_eq!(
+            survey[0],
+            ChannelSurvey {
+                frequency: 2412,
+                in_use: false,
+                noise: Some(-93),
+                active_time: Some(105),
+                busy_time: Some(61),
+                receive_time: Some(48),
+                transmit_time: Some(0),
+            }
+        );
+
+        let in_use = survey
+            .iter()
+            .filter(|survey| survey.in_use)
+            .collect::<Vec<_>>();
+        assert_eq!(in_use.len(), 1);
+        assert_eq!(in_use[0].frequency, 2437);
+        assert_eq!(in_use[0].busy_time, Some(17730));
+        assert_eq!(survey[12].frequency, 2472);
+    }
+
+    #[test]
+    fn parses_mt7921e_survey_without_noise() {
+        let survey = parse_iw_survey(include_str!("../../fixtures/iw/survey/mt7921e.txt"));
+        assert_eq!(survey.len(), 11);
+        assert!(survey.iter().all(|survey| survey.noise.is_none()));
+        assert!(survey.iter().all(|survey| survey.receive_time.is_none()));
+
+        let in_use = &survey[2];
+        assert_eq!(in_use.frequency, 5220);
+        assert!(in_use.in_use);
+        assert_eq!(in_use.transmit_time, Some(811));
+        assert_eq!(in_use.busy_ratio(), Some(9420.0 / 31502.0));
+
+        assert_eq!(survey[10].frequency, 5995);
+    }
+}
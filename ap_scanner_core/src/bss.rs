@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+/// Load the AP reports about itself in its BSS Load element.
+/// Kept as announced for the reading and its exports, the planners don't weigh it: it is the
+/// view of a single AP, and its signal already counts for the interference it causes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BssLoad {
+    pub station_count: u16,
+    // share of time the AP sensed the medium busy, out of 255
+    pub channel_utilization: u8,
+    // remaining admission capacity, in units of 32 us per second
+    pub admission_capacity: u16,
+}
+
+/// Regulatory domain announced in the Country element.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Country {
+    pub code: String,
+    pub environment: Environment,
+    pub power_limits: Vec<PowerLimit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Environment {
+    Any,
+    Indoor,
+    Outdoor,
+    NonCountry,
+}
+
+/// Maximum transmit power over a run of channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PowerLimit {
+    pub first_channel: u8,
+    pub last_channel: u8,
+    pub max_power_dbm: i8,
+}
+
+impl Environment {
+    /// From the third byte of the country string.
+    pub fn from_byte(byte: u8) -> Self {
+        match byte {
+            b'I' => Self::Indoor,
+            b'O' => Self::Outdoor,
+            b'X' => Self::NonCountry,
+            _ => Self::Any,
+        }
+    }
+
+    /// From the text `iw` prints after "Environment:".
+    pub fn from_iw(name: &str) -> Self {
+        match name {
+            "Indoor only" => Self::Indoor,
+            "Outdoor only" => Self::Outdoor,
+            "Non-country" => Self::NonCountry,
+            _ => Self::Any,
+        }
+    }
+}
+
+impl PowerLimit {
+    /// From a subband triplet, channels are 4 apart above 2.4 GHz.
+    pub fn from_triplet(first_channel: u8, count: u8, max_power_dbm: i8) -> Self {
+        let step = if first_channel <= 14 { 1 } else { 4 };
+
+        Self {
+            first_channel,
+            last_channel: first_channel.saturating_add(step * count.saturating_sub(1)),
+            max_power_dbm,
+        }
+    }
+}
+
+/// The capability information field of beacons and probe responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Capability(pub u16);
+
+// names as printed by `iw`, indexed by bit
+const CAPABILITY_NAMES: &[&str] = &[
+    "ESS",
+    "IBSS",
+    "CfPollable",
+    "CfPollReq",
+    "Privacy",
+    "ShortPreamble",
+    "PBCC",
+    "ChannelAgility",
+    "SpectrumMgmt",
+    "QoS",
+    "ShortSlotTime",
+    "APSD",
+    "RadioMeasure",
+    "DSSS-OFDM",
+    "DelayedBACK",
+    "ImmediateBACK",
+];
+
+impl Capability {
+    pub const PRIVACY: u16 = 1 << 4;
+
+    pub fn has(&self, flag: u16) -> bool {
+        self.0 & flag != 0
+    }
+
+    /// From the "capability" line of `iw`, e.g. "ESS Privacy ShortSlotTime (0x0411)".
+    /// The raw value is used when present, the flag names otherwise.
+    pub fn from_iw(line: &str) -> Self {
+        let raw = line
+            .split_once("(0x")
+            .and_then(|(_, hex)| hex.split(')').next())
+            .and_then(|hex| u16::from_str_radix(hex, 16).ok());
+
+        match raw {
+            Some(raw) => Self(raw),
+            None => Self(
+                line.split_whitespace()
+                    .filter_map(|name| CAPABILITY_NAMES.iter().position(|cap| *cap == name))
+                    .fold(0, |caps, bit| caps | 1 << bit),
+            ),
+        }
+    }
+}
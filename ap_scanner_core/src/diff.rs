@@ -0,0 +1,263 @@
+// What changed between two measures of the same local.
+//
+// Networks are matched by BSSID. A network may be in one measure only, or move to another
+// channel or width, be heard louder or quieter, or change its security when both measures know
+// it. Signals swing by a few dB from one scan to the next, so only changes of at least the
+// threshold are reported.
+use super::security::Security;
+use super::wifi::Wifi;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Display};
+
+// smaller signal changes are scan to scan noise
+pub const THRESHOLD_DB: f32 = 6.0;
+
+#[derive(Debug, Serialize)]
+pub struct Network {
+    pub ssid: String,
+    pub mac: String,
+    pub channel: u8,
+    pub width: u16,
+    pub signal: f32,
+}
+
+impl From<&Wifi> for Network {
+    fn from(wifi: &Wifi) -> Self {
+        Self {
+            ssid: wifi.ssid.clone(),
+            mac: wifi.mac.clone(),
+            channel: wifi.channel,
+            width: wifi.width.mhz(),
+            signal: wifi.signal,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Change<T> {
+    pub before: T,
+    pub after: T,
+}
+
+/// A network in both measures, only what changed is set.
+#[derive(Debug, Serialize)]
+pub struct Changed {
+    pub ssid: String,
+    pub mac: String,
+    pub channel: Option<Change<u8>>,
+    pub width: Option<Change<u16>>,
+    pub signal: Option<Change<f32>>,
+    // `None` as well when either measure doesn't know it
+    pub security: Option<Change<Security>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Diff {
+    // smallest signal change reported, in dB
+    pub threshold: f32,
+    pub appeared: Vec<Network>,
+    pub vanished: Vec<Network>,
+    pub changed: Vec<Changed>,
+}
+
+/// The networks by BSSID, the loudest when the same BSS shows up twice.
+fn by_mac(networks: &[Wifi]) -> BTreeMap<String, &Wifi> {
+    let mut map: BTreeMap<String, &Wifi> = BTreeMap::new();
+
+    for wifi in networks {
+        let entry = map.entry(wifi.mac.to_lowercase()).or_insert(wifi);
+
+        if wifi.signal > entry.signal {
+            *entry = wifi;
+        }
+    }
+
+    map
+}
+
+fn change<T: PartialEq>(before: T, after: T) -> Option<Change<T>> {
+    match before == after {
+        true => None,
+        false => Some(Change { before, after }),
+    }
+}
+
+impl Diff {
+    /// Compares the networks of two measures, `before` taken first.
+    pub fn new(before: &[Wifi], after: &[Wifi], threshold: f32) -> Self {
+        let before = by_mac(before);
+        let after = by_mac(after);
+
+        let appeared = after
+            .iter()
+            .filter(|(mac, _)| !before.contains_key(*mac))
+            .map(|(_, wifi)| Network::from(*wifi))
+            .collect();
+
+        let vanished = before
+            .iter()
+            .filter(|(mac, _)| !after.contains_key(*mac))
+            .map(|(_, wifi)| Network::from(*wifi))
+            .collect();
+
+        let changed = before
+            .iter()
+            .filter_map(|(mac, old)| Some((*old, *after.get(mac)?)))
+            .map(|(old, new)| Changed {
+                ssid: new.ssid.clone(),
+                mac: new.mac.clone(),
+                channel: change(old.channel, new.channel),
+                width: change(old.width.mhz(), new.width.mhz()),
+                signal: match (new.signal - old.signal).abs() >= threshold {
+                    true => Some(Change {
+                        before: old.signal,
+                        after: new.signal,
+                    }),
+                    false => None,
+                },
+                security: match (&old.security, &new.security) {
+                    (Some(before), Some(after)) => change(before.clone(), after.clone()),
+                    _ => None,
+                },
+            })
+            .filter(|changed| {
+                changed.channel.is_some()
+                    || changed.width.is_some()
+                    || changed.signal.is_some()
+                    || changed.security.is_some()
+            })
+            .collect();
+
+        Self {
+            threshold,
+            appeared,
+            vanished,
+            changed,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.appeared.is_empty() && self.vanished.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {} on channel {} at {}MHz, heard at {:.0} dBm.",
+            self.ssid, self.mac, self.channel, self.width, self.signal
+        )
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No changes.");
+        }
+
+        let mut lines = vec![];
+
+        if !self.appeared.is_empty() {
+            lines.push("Appeared:".to_string());
+            lines.extend(self.appeared.iter().map(|network| format!("\t{}", network)));
+        }
+
+        if !self.vanished.is_empty() {
+            lines.push("Vanished:".to_string());
+            lines.extend(self.vanished.iter().map(|network| format!("\t{}", network)));
+        }
+
+        if !self.changed.is_empty() {
+            lines.push("Changed:".to_string());
+        }
+
+        for changed in &self.changed {
+            lines.push(format!("\t{}, {}:", changed.ssid, changed.mac));
+
+            if let Some(channel) = &changed.channel {
+                lines.push(format!(
+                    "\t\tChannel {} to {}.",
+                    channel.before, channel.after
+                ));
+            }
+
+            if let Some(width) = &changed.width {
+                lines.push(format!(
+                    "\t\tWidth {}MHz to {}MHz.",
+                    width.before, width.after
+                ));
+            }
+
+            if let Some(signal) = &changed.signal {
+                lines.push(format!(
+                    "\t\tSignal {:.0} dBm to {:.0} dBm, {:+.0} dB.",
+                    signal.before,
+                    signal.after,
+                    signal.after - signal.before
+                ));
+            }
+
+            if let Some(change) = &changed.security {
+                lines.push(format!(
+                    "\t\tSecurity {} to {}.",
+                    change.before, change.after
+                ));
+            }
+        }
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        security::{Akm, AuthType, Cipher},
+        wifi::test_wifi,
+    };
+
+    fn security(auth: AuthType, akm: Akm) -> Option<Security> {
+        Some(Security {
+            auth,
+            akm_suites: vec![akm],
+            pairwise_ciphers: vec![Cipher::Ccmp],
+            group_cipher: Some(Cipher::Ccmp),
+            pmf_required: false,
+            pmf_capable: false,
+            wps: false,
+        })
+    }
+
+    fn networks() -> Vec<Wifi> {
+        let mut office = test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -47.0);
+        office.security = security(AuthType::Wpa2, Akm::Psk);
+        let mut guests = test_wifi("Guests", "3c:37:86:1a:2b:3e", 6, -48.0);
+        guests.security = security(AuthType::Wpa3, Akm::Sae);
+
+        vec![office, guests]
+    }
+
+    #[test]
+    fn unknown_security_is_no_change() {
+        let before = networks();
+        let mut after = networks();
+        after[0].security = None;
+
+        assert!(Diff::new(&before, &after, THRESHOLD_DB).is_empty());
+        assert!(Diff::new(&after, &before, THRESHOLD_DB).is_empty());
+    }
+
+    #[test]
+    fn security_change_is_reported() {
+        let before = networks();
+        let mut after = networks();
+        after[0].security = after[1].security.clone();
+
+        let diff = Diff::new(&before, &after, THRESHOLD_DB);
+        assert_eq!(diff.changed.len(), 1);
+        assert!(diff.changed[0].security.is_some());
+    }
+}
@@ -0,0 +1,144 @@
+use super::wifi::Wifi;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// The networks we manage, by BSSID or SSID.
+/// Everything else belongs to someone else and is planned around, never moved.
+/// An empty fleet manages every network in the scan.
+#[derive(Debug, Default, Clone)]
+pub struct Fleet {
+    bssids: Vec<String>,
+    ssids: Vec<String>,
+}
+
+impl Fleet {
+    /// Entries shaped like a MAC address are taken as BSSIDs, anything else as an SSID.
+    pub fn new(entries: impl IntoIterator<Item = String>) -> Self {
+        let mut fleet = Self::default();
+        fleet.extend(entries);
+        fleet
+    }
+
+    /// Adds the entries of a file, one per line. Blank lines and lines starting with `#` are skipped.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to open fleet file {:?}", path))?;
+
+        self.extend(
+            data.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string),
+        );
+
+        Ok(())
+    }
+
+    fn extend(&mut self, entries: impl IntoIterator<Item = String>) {
+        for entry in entries {
+            if is_mac(&entry) {
+                self.bssids.push(entry.to_lowercase());
+            } else {
+                self.ssids.push(entry);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bssids.is_empty() && self.ssids.is_empty()
+    }
+
+    pub fn owns(&self, wifi: &Wifi) -> bool {
+        self.is_empty()
+            || self.bssids.contains(&wifi.mac.to_lowercase())
+            || self.ssids.contains(&wifi.ssid)
+    }
+}
+
+fn is_mac(entry: &str) -> bool {
+    let octets = entry.split(':').collect::<Vec<_>>();
+
+    octets.len() == 6
+        && octets
+            .iter()
+            .all(|octet| octet.len() == 2 && u8::from_str_radix(octet, 16).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        planner::{self, Greedy},
+        reading::{Reading, Suggestion},
+        wifi::test_wifi,
+    };
+    use std::collections::BTreeMap;
+
+    fn fleet(entries: &[&str]) -> Fleet {
+        Fleet::new(entries.iter().map(|entry| entry.to_string()))
+    }
+
+    #[test]
+    fn bssids_match_in_any_case() {
+        let ours = test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -50.0);
+        let mut shouted = test_wifi("Office", "3C:37:86:1A:2B:3E", 6, -50.0);
+
+        assert!(fleet(&["3C:37:86:1A:2B:3D"]).owns(&ours));
+        assert!(fleet(&["3c:37:86:1a:2b:3e"]).owns(&shouted));
+        assert!(!fleet(&["3c:37:86:1a:2b:3d"]).owns(&shouted));
+
+        // the SSID alone doesn't make it ours when only BSSIDs are listed
+        shouted.mac = "02:00:00:00:00:01".to_string();
+        assert!(!fleet(&["3c:37:86:1a:2b:3e"]).owns(&shouted));
+    }
+
+    #[test]
+    fn ssids_match_exactly() {
+        let office = test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -50.0);
+
+        assert!(fleet(&["Office"]).owns(&office));
+        assert!(!fleet(&["office"]).owns(&office));
+        assert!(!fleet(&["Office 5G"]).owns(&office));
+        // not six octets, so an SSID
+        assert!(!fleet(&["3c:37:86:1a:2b"]).owns(&office));
+        assert!(fleet(&[]).owns(&office));
+    }
+
+    #[test]
+    fn unowned_networks_keep_their_channel() {
+        let networks = vec![
+            test_wifi("Office", "02:00:00:00:00:01", 6, -50.0),
+            test_wifi("Neighbour", "02:00:00:00:00:02", 6, -55.0),
+        ];
+        let heard = vec![vec![Some(-50.0)], vec![Some(-55.0)]];
+        let fleet = fleet(&["Office"]);
+        let owned = networks
+            .iter()
+            .map(|wifi| fleet.owns(wifi))
+            .collect::<Vec<_>>();
+
+        let plan = planner::plan_2g(&networks, &heard, &owned, &[1, 6, 11], &Greedy, 0);
+        assert_ne!(plan[0].0, 6);
+        assert_eq!(plan[1].0, 6);
+
+        let reading = Reading::plan(
+            "Office".to_string(),
+            networks.into_iter().zip(heard).collect::<Vec<_>>(),
+            None,
+            BTreeMap::new(),
+            &fleet,
+            &Greedy,
+            0,
+        )
+        .unwrap();
+        let neighbour = reading.wifi_2_4_ghz[&6]
+            .iter()
+            .find(|(wifi, _)| wifi.ssid == "Neighbour")
+            .unwrap();
+
+        assert!(matches!(neighbour.1, Suggestion::Fixed));
+        assert!(!reading.rationale.contains_key("02:00:00:00:00:02"));
+        assert!(!reading.power.contains_key("02:00:00:00:00:02"));
+    }
+}
@@ -0,0 +1,18 @@
+// The model and the planning shared by the scanner and the server.
+//
+// Networks, readings and measures as they are saved and uploaded, the regulatory rules, the
+// channel, width and power planning, and what is derived from a reading: diffs, interference
+// matrices and site plans. Scanning and presenting stay with each binary.
+pub mod bss;
+pub mod diff;
+pub mod fleet;
+pub mod matrix;
+pub mod measure;
+pub mod planner;
+pub mod power;
+pub mod reading;
+pub mod regulatory;
+pub mod security;
+pub mod site;
+pub mod survey;
+pub mod wifi;
@@ -0,0 +1,257 @@
+// BSS by BSS interference matrix of a reading.
+//
+// Each cell holds how much of the spectrum of a source network lands on the channels a victim
+// network uses today, and the interference that puts on the victim: that share of the power the
+// source was heard with. It is the sum the planners score channels with, so the loudest cells of
+// a row are the neighbours behind the suggestion of its network. Networks of different bands
+// don't overlap.
+use super::planner;
+use super::reading::{Reading, Suggestion};
+use anyhow::Context;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct MatrixNetwork {
+    pub ssid: String,
+    pub mac: String,
+    pub channel: u8,
+    pub signal: f32,
+    // whether it was planned, the others are left where they are
+    pub planned: bool,
+}
+
+/// Rows are the victims and columns the sources, both in the order of `networks`.
+#[derive(Debug, Serialize)]
+pub struct InterferenceMatrix {
+    pub networks: Vec<MatrixNetwork>,
+    // share of the power of the source landing on the channels of the victim, between 0 and 1
+    pub overlap: Vec<Vec<f32>>,
+    // interference in dBm the source puts on the victim, `None` when they don't overlap
+    pub interference: Vec<Vec<Option<f32>>>,
+}
+
+/// Quotes a CSV field when it holds a separator, a quote or a line break, as SSIDs may.
+pub fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+impl InterferenceMatrix {
+    /// The matrix of every network of `reading`, 2.4, 5 and 6 GHz ones in that order.
+    pub fn new(reading: &Reading) -> Self {
+        let wifis = reading
+            .wifi_2_4_ghz
+            .values()
+            .chain(reading.wifi_5_ghz.values())
+            .chain(reading.wifi_6_ghz.values())
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let overlap = wifis
+            .iter()
+            .enumerate()
+            .map(|(row, (victim, _))| {
+                wifis
+                    .iter()
+                    .enumerate()
+                    .map(|(column, (source, _))| match row == column {
+                        true => 0.0,
+                        false => planner::overlap(victim, source),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let interference = overlap
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&wifis)
+                    .map(|(share, (source, _))| match *share > 0.0 {
+                        true => Some(source.signal + 10.0 * share.log10()),
+                        false => None,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            networks: wifis
+                .iter()
+                .map(|(wifi, suggestion)| MatrixNetwork {
+                    ssid: wifi.ssid.clone(),
+                    mac: wifi.mac.clone(),
+                    channel: wifi.channel,
+                    signal: wifi.signal,
+                    planned: !matches!(suggestion, Suggestion::Fixed),
+                })
+                .collect(),
+            overlap,
+            interference,
+        }
+    }
+
+    /// One line per victim and source pair, victims in the order of `networks`.
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from(
+            "victim_ssid,victim_mac,victim_channel,source_ssid,source_mac,source_channel,source_signal,overlap,interference\n",
+        );
+
+        for (row, victim) in self.networks.iter().enumerate() {
+            for (column, source) in self.networks.iter().enumerate() {
+                if row == column {
+                    continue;
+                }
+
+                output.push_str(&format!(
+                    "{},{},{},{},{},{},{:.0},{:.3},{}\n",
+                    csv_field(&victim.ssid),
+                    victim.mac,
+                    victim.channel,
+                    csv_field(&source.ssid),
+                    source.mac,
+                    source.channel,
+                    source.signal,
+                    self.overlap[row][column],
+                    self.interference[row][column]
+                        .map(|dbm| format!("{:.1}", dbm))
+                        .unwrap_or_default(),
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Saves the matrix to `path`, as CSV when it ends in `.csv` and as json otherwise.
+    pub fn save(&self, path: String) -> anyhow::Result<()> {
+        match path.ends_with(".csv") {
+            true => std::fs::write(&path, self.to_csv())
+                .with_context(|| format!("Failed to save the interference matrix to {:?}", path)),
+            false => {
+                let file = std::fs::File::create(&path)
+                    .with_context(|| format!("Failed to open file {:?}", path))?;
+
+                serde_json::to_writer_pretty(file, self).with_context(|| {
+                    format!("Failed to save the interference matrix to {:?}", path)
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fleet::Fleet,
+        planner::Greedy,
+        wifi::{test_wifi, Width, Wifi},
+    };
+    use std::collections::BTreeMap;
+
+    fn matrix(networks: Vec<Wifi>) -> InterferenceMatrix {
+        let networks = networks
+            .into_iter()
+            .map(|wifi| {
+                let signal = wifi.signal;
+                (wifi, vec![Some(signal)])
+            })
+            .collect();
+        let reading = Reading::plan(
+            "Office".to_string(),
+            networks,
+            None,
+            BTreeMap::new(),
+            &Fleet::default(),
+            &Greedy,
+            0,
+        )
+        .unwrap();
+
+        InterferenceMatrix::new(&reading)
+    }
+
+    fn cell(matrix: &InterferenceMatrix, victim: &str, source: &str) -> (f32, Option<f32>) {
+        let idx = |mac: &str| {
+            matrix
+                .networks
+                .iter()
+                .position(|network| network.mac == mac)
+                .unwrap()
+        };
+        let (row, column) = (idx(victim), idx(source));
+
+        (
+            matrix.overlap[row][column],
+            matrix.interference[row][column],
+        )
+    }
+
+    #[test]
+    fn co_channel_takes_it_all_adjacent_a_share() {
+        let matrix = matrix(vec![
+            test_wifi("Office", "02:00:00:00:00:01", 6, -50.0),
+            test_wifi("Same", "02:00:00:00:00:02", 6, -60.0),
+            test_wifi("Next", "02:00:00:00:00:03", 8, -60.0),
+        ]);
+
+        assert_eq!(
+            cell(&matrix, "02:00:00:00:00:01", "02:00:00:00:00:02"),
+            (1.0, Some(-60.0))
+        );
+
+        // a 22 MHz transmission two channels, 10 MHz, away still overlaps 12 MHz of the victim
+        let (share, dbm) = cell(&matrix, "02:00:00:00:00:01", "02:00:00:00:00:03");
+        assert!((share - 12.0 / 22.0).abs() < 1e-6);
+        assert!((dbm.unwrap() - (-60.0 + 10.0 * (12.0f32 / 22.0).log10())).abs() < 1e-4);
+    }
+
+    #[test]
+    fn wider_source_lands_in_part() {
+        let mut wide = test_wifi("Wide", "02:00:00:00:00:02", 36, -60.0);
+        wide.width = Width::MHz40;
+        wide.center_channel = Some(38);
+
+        let matrix = matrix(vec![
+            test_wifi("Office", "02:00:00:00:00:01", 36, -50.0),
+            wide,
+            test_wifi("Other band", "02:00:00:00:00:03", 6, -40.0),
+        ]);
+
+        // half of the 40 MHz source falls on the 20 MHz victim, all of the victim on the source
+        let (share, dbm) = cell(&matrix, "02:00:00:00:00:01", "02:00:00:00:00:02");
+        assert_eq!(share, 0.5);
+        assert!((dbm.unwrap() - (-63.0103)).abs() < 1e-3);
+        assert_eq!(
+            cell(&matrix, "02:00:00:00:00:02", "02:00:00:00:00:01"),
+            (1.0, Some(-50.0))
+        );
+        assert_eq!(
+            cell(&matrix, "02:00:00:00:00:01", "02:00:00:00:00:03"),
+            (0.0, None)
+        );
+    }
+
+    #[test]
+    fn csv_quotes_awkward_ssids() {
+        let matrix = matrix(vec![
+            test_wifi("Cafe, \"Bar\"", "02:00:00:00:00:01", 6, -50.0),
+            test_wifi("Plain", "02:00:00:00:00:02", 6, -60.0),
+        ]);
+        let csv = matrix.to_csv();
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "\"Cafe, \"\"Bar\"\"\",02:00:00:00:00:01,6,Plain,02:00:00:00:00:02,6,-60,1.000,-60.0"
+        );
+        assert_eq!(
+            lines[2],
+            "Plain,02:00:00:00:00:02,6,\"Cafe, \"\"Bar\"\"\",02:00:00:00:00:01,6,-50,1.000,-50.0"
+        );
+    }
+}
@@ -0,0 +1,234 @@
+// Raw measures, what a scan saw before any analysis.
+//
+// A measure keeps the networks as they were reported and the survey data, nothing derived from
+// them, so it can be analysed again later with another planner, country, fleet or seed. The
+// analysis is a `Reading`, saved on its own.
+use super::fleet::Fleet;
+use super::planner::ChannelPlanner;
+use super::reading::Reading;
+use super::regulatory;
+use super::survey::ChannelSurvey;
+use super::wifi::Wifi;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Measure {
+    // time since epoch in milliseconds
+    pub timestamp: u128,
+    // identifier of the local this measure was taken
+    pub local: String,
+    // every network in range, as reported
+    pub networks: Vec<Wifi>,
+    // survey data of every channel the driver reported, by frequency in MHz
+    #[serde(default)]
+    pub survey: BTreeMap<u16, ChannelSurvey>,
+    // what went wrong while taking it, for the caller to report, never saved
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+impl Measure {
+    /// A measure of the networks heard just now, with the survey data of the channels the
+    /// driver reported and what went wrong while taking it.
+    pub fn new(
+        local: String,
+        networks: Vec<Wifi>,
+        survey: Vec<ChannelSurvey>,
+        warnings: Vec<String>,
+    ) -> Result<Self> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .with_context(|| anyhow!("Failed to get timestamp."))?
+            .as_millis();
+
+        Ok(Self {
+            timestamp,
+            local,
+            networks,
+            survey: survey
+                .into_iter()
+                .map(|survey| (survey.frequency, survey))
+                .collect(),
+            warnings,
+        })
+    }
+
+    /// The country the networks announce, see `regulatory::detect_country`.
+    pub fn country(&self) -> Option<String> {
+        regulatory::detect_country(&self.networks)
+    }
+
+    /// Analyses the measure. Suggestions follow the rules of `country`, or of the country the
+    /// networks announce when not given.
+    /// Only the networks of the `fleet` are planned, the others are `Suggestion::Fixed`.
+    /// Random choices are drawn from `seed`, which the reading keeps.
+    pub fn analyse(
+        self,
+        country: Option<String>,
+        fleet: &Fleet,
+        planner: &dyn ChannelPlanner,
+        seed: u64,
+    ) -> Result<Reading> {
+        let country = country
+            .map(|code| code.trim().to_uppercase())
+            .or_else(|| self.country());
+
+        let networks = self
+            .networks
+            .into_iter()
+            .map(|wifi| {
+                let signal = wifi.signal;
+                (wifi, vec![Some(signal)])
+            })
+            .collect();
+
+        let mut reading = Reading::plan(
+            self.local,
+            networks,
+            country,
+            self.survey,
+            fleet,
+            planner,
+            seed,
+        )?;
+
+        // the analysis is of the moment the measure was taken
+        reading.timestamp = self.timestamp;
+
+        Ok(reading)
+    }
+
+    /// Loads a measure, or the networks of a reading saved before measures were kept apart.
+    pub fn load(path: String) -> Result<Self> {
+        let data = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+
+        serde_json::from_str::<Self>(&data)
+            .or_else(|measure_err| {
+                serde_json::from_str::<Reading>(&data)
+                    .map(Self::from)
+                    .with_context(|| format!("Not a measure ({}), nor a reading", measure_err))
+            })
+            .with_context(|| format!("Failed to load measure from {:?}", path))
+    }
+
+    pub fn save(&self, path: String) -> Result<()> {
+        let file = std::fs::File::create(&path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+
+        serde_json::to_writer_pretty(file, self)
+            .with_context(|| format!("Failed to save measure to {:?}", path))
+    }
+}
+
+impl From<Reading> for Measure {
+    fn from(reading: Reading) -> Self {
+        Self {
+            timestamp: reading.timestamp,
+            local: reading.local,
+            networks: reading
+                .wifi_2_4_ghz
+                .into_values()
+                .chain(reading.wifi_5_ghz.into_values())
+                .chain(reading.wifi_6_ghz.into_values())
+                .flatten()
+                .map(|(wifi, _)| wifi)
+                .collect(),
+            survey: reading.survey,
+            warnings: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{planner::Greedy, wifi::test_wifi};
+
+    /// A file of its own in the temporary directory, removed once dropped.
+    struct TempFile(String);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "ap_scanner_{}_{}.json",
+                std::process::id(),
+                name
+            ));
+
+            Self(path.to_string_lossy().into_owned())
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn office() -> Measure {
+        let mut measure = Measure::new(
+            "Office".to_string(),
+            vec![
+                test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -50.0),
+                test_wifi("Cafe", "02:00:00:00:00:01", 11, -80.0),
+            ],
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        measure.timestamp = 1_650_000_000_000;
+        measure
+    }
+
+    #[test]
+    fn loads_a_measure() {
+        let file = TempFile::new("measure");
+        office().save(file.0.clone()).unwrap();
+
+        let measure = Measure::load(file.0.clone()).unwrap();
+        assert_eq!(measure.local, "Office");
+        assert_eq!(measure.timestamp, 1_650_000_000_000);
+        assert_eq!(measure.networks.len(), 2);
+    }
+
+    #[test]
+    fn loads_the_networks_of_a_legacy_reading() {
+        let file = TempFile::new("reading");
+        let reading = office()
+            .analyse(None, &Fleet::default(), &Greedy, 7)
+            .unwrap();
+        reading.serialize(file.0.clone()).unwrap();
+
+        let measure = Measure::load(file.0.clone()).unwrap();
+        assert_eq!(measure.local, "Office");
+        assert_eq!(measure.timestamp, 1_650_000_000_000);
+        assert_eq!(
+            measure
+                .networks
+                .iter()
+                .map(|wifi| wifi.mac.as_str())
+                .collect::<Vec<_>>(),
+            ["3c:37:86:1a:2b:3d", "02:00:00:00:00:01"]
+        );
+    }
+
+    #[test]
+    fn neither_is_an_error() {
+        let file = TempFile::new("neither");
+        std::fs::write(&file.0, "{\"local\": \"Office\"}").unwrap();
+
+        let err = format!("{:#}", Measure::load(file.0.clone()).unwrap_err());
+        assert!(err.starts_with("Failed to load measure from"), "{}", err);
+        assert!(err.contains(": Not a measure ("), "{}", err);
+        assert!(err.contains("), nor a reading: "), "{}", err);
+
+        let err = format!("{:#}", Reading::deserialize(file.0.clone()).unwrap_err());
+        assert!(err.starts_with("Failed to load reading from"), "{}", err);
+    }
+}
@@ -0,0 +1,144 @@
+use super::{objective, ChannelPlanner, Greedy, Problem};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Simulated annealing over the whole plan, starting from the greedy one.
+///
+/// Each step moves a random movable network to a random other candidate and keeps the change
+/// if it lowers the total interference of the band, or by chance if it raises it, less and less
+/// so as the temperature drops. Unlike the greedy planner it can accept a move that hurts one
+/// network when it helps the others more, which pays off on large fleets. The best plan seen is
+/// returned, so it never does worse than the greedy one. A move only changes the cost of the
+/// moved network and of those heard along with it, only those are scored again.
+pub struct Annealing {
+    // steps per movable network
+    pub steps: usize,
+    // in dB of total interference, a move that costs this much is taken about a third of the
+    // time at the start
+    pub start_temperature: f32,
+    pub end_temperature: f32,
+}
+
+impl Default for Annealing {
+    fn default() -> Self {
+        Self {
+            steps: 100,
+            start_temperature: 1.0,
+            end_temperature: 0.01,
+        }
+    }
+}
+
+impl ChannelPlanner for Annealing {
+    fn plan(&self, problem: &Problem, seed: u64) -> Vec<u8> {
+        let mut plan = Greedy.plan(problem, seed);
+
+        let movable = (0..problem.networks.len())
+            .filter(|idx| problem.movable[*idx])
+            .collect::<Vec<_>>();
+
+        if movable.is_empty() || problem.candidates.len() < 2 {
+            return plan;
+        }
+
+        // same numbers on every platform and rand release, unlike `StdRng`
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let steps = self.steps * movable.len();
+        let cooling = (self.end_temperature / self.start_temperature).powf(1.0 / steps as f32);
+
+        let mut footprints = problem.footprints(&plan);
+        let mut costs = problem.costs(&plan);
+        let linked = (0..problem.networks.len())
+            .map(|idx| problem.linked(idx))
+            .collect::<Vec<_>>();
+
+        let mut energy = objective(&costs);
+        let mut best = (plan.clone(), energy);
+        let mut temperature = self.start_temperature;
+
+        for _ in 0..steps {
+            let idx = *movable.choose(&mut rng).expect("movable networks");
+            let candidate = *problem
+                .candidates
+                .choose(&mut rng)
+                .expect("two candidates or more");
+
+            if candidate != plan[idx] {
+                let previous = std::mem::replace(&mut plan[idx], candidate);
+                let footprint =
+                    std::mem::replace(&mut footprints[idx], problem.footprint(idx, candidate));
+
+                let before = std::iter::once(idx)
+                    .chain(linked[idx].iter().copied())
+                    .map(|other| {
+                        let cost = problem.cost_in(other, &plan, &footprints);
+                        (other, std::mem::replace(&mut costs[other], cost))
+                    })
+                    .collect::<Vec<_>>();
+                let next = objective(&costs);
+
+                if next <= energy || rng.gen::<f32>() < ((energy - next) / temperature).exp() {
+                    energy = next;
+
+                    if energy < best.1 {
+                        best = (plan.clone(), energy);
+                    }
+                } else {
+                    plan[idx] = previous;
+                    footprints[idx] = footprint;
+                    for (other, cost) in before {
+                        costs[other] = cost;
+                    }
+                }
+            }
+
+            temperature *= cooling;
+        }
+
+        best.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::crowded;
+    use super::*;
+
+    #[test]
+    fn crowded_channel_is_spread_out() {
+        let (networks, heard) = crowded(3, 1);
+        let movable = [true; 3];
+        let problem = Problem::new_2g(&networks, &heard, &movable, &[1, 6, 11]);
+        let mut plan = Annealing::default().plan(&problem, 0);
+
+        assert!(problem.score(&plan).worst.is_none());
+        plan.sort_unstable();
+        assert_eq!(plan, vec![1, 6, 11]);
+    }
+
+    #[test]
+    fn same_seed_same_plan() {
+        let (networks, heard) = crowded(9, 4);
+        let movable = [true; 9];
+        let problem = Problem::new_2g(&networks, &heard, &movable, &[1, 6, 11]);
+
+        assert_eq!(
+            Annealing::default().plan(&problem, 42),
+            Annealing::default().plan(&problem, 42)
+        );
+    }
+
+    #[test]
+    fn never_worse_than_greedy() {
+        let (networks, heard) = crowded(9, 4);
+        let movable = [true, true, false, true, true, true, false, true, true];
+        let problem = Problem::new_2g(&networks, &heard, &movable, &[1, 6, 11]);
+        let greedy = problem.objective(&Greedy.plan(&problem, 0));
+
+        for seed in 0..8 {
+            let plan = Annealing::default().plan(&problem, seed);
+
+            assert!(problem.objective(&plan) <= greedy, "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::{to_mw, ChannelPlanner, Problem};
+use std::cmp::Ordering;
+
+/// Colours the interference graph, candidates being the colours.
+///
+/// Two networks are linked when they are heard at the same point, the louder the stronger the
+/// link. The movable networks start off the air and are coloured one at a time, always the one
+/// with the most candidates already taken by its coloured neighbours, the busiest one on a tie
+/// (DSatur). It goes on the candidate its coloured neighbours interfere with the least, networks
+/// that can't be moved being coloured from the start. Unlike the greedy planner a network is
+/// never weighed against where a movable one is today, only against where it was put.
+pub struct Colouring;
+
+impl ChannelPlanner for Colouring {
+    fn plan(&self, problem: &Problem, _seed: u64) -> Vec<u8> {
+        let count = problem.networks.len();
+        let mut plan = problem.current();
+
+        let mut footprints = problem.footprints_today();
+        let mut coloured = problem
+            .movable
+            .iter()
+            .map(|movable| !movable)
+            .collect::<Vec<_>>();
+
+        for idx in 0..count {
+            if !coloured[idx] {
+                footprints[idx].clear();
+            }
+        }
+
+        // how loud each network is to the others, summed over its links
+        let degree = (0..count)
+            .map(|idx| {
+                (0..count)
+                    .filter(|other| *other != idx)
+                    .map(|other| link(problem, idx, other))
+                    .sum::<f32>()
+            })
+            .collect::<Vec<_>>();
+
+        while let Some(idx) = (0..count)
+            .filter(|idx| !coloured[*idx])
+            .map(|idx| {
+                let saturation = problem
+                    .candidates
+                    .iter()
+                    .filter(|candidate| problem.cost(idx, **candidate, &footprints) > 0.0)
+                    .count();
+
+                (idx, saturation)
+            })
+            .max_by(|(a, sat_a), (b, sat_b)| {
+                sat_a
+                    .cmp(sat_b)
+                    .then_with(|| {
+                        degree[*a]
+                            .partial_cmp(&degree[*b])
+                            .unwrap_or(Ordering::Equal)
+                    })
+                    .then_with(|| problem.networks[*b].mac.cmp(&problem.networks[*a].mac))
+            })
+            .map(|(idx, _)| idx)
+        {
+            if let Some(best) = problem.best(idx, &footprints) {
+                plan[idx] = best;
+            }
+
+            footprints[idx] = problem.footprint(idx, plan[idx]);
+            coloured[idx] = true;
+        }
+
+        plan
+    }
+}
+
+/// Strength of the link between two networks, in mW. The loudest either is heard with at a
+/// point where both are.
+fn link(problem: &Problem, a: usize, b: usize) -> f32 {
+    problem.heard[a]
+        .iter()
+        .zip(&problem.heard[b])
+        .filter_map(|(a, b)| Some((*a)?.max((*b)?)))
+        .map(to_mw)
+        .fold(0.0, f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::crowded;
+    use super::*;
+
+    #[test]
+    fn crowded_channel_is_spread_out() {
+        let (networks, heard) = crowded(3, 1);
+        let movable = [true; 3];
+        let problem = Problem::new_2g(&networks, &heard, &movable, &[1, 6, 11]);
+        let mut plan = Colouring.plan(&problem, 0);
+
+        assert!(problem.score(&plan).worst.is_none());
+        plan.sort_unstable();
+        assert_eq!(plan, vec![1, 6, 11]);
+    }
+}
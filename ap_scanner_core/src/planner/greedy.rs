@@ -0,0 +1,62 @@
+use super::{ChannelPlanner, Problem};
+
+// a plan settles in a couple of passes, this only guards against two networks trading places
+const MAX_PASSES: usize = 8;
+
+/// Places the movable networks one at a time on the candidate with the least interference.
+///
+/// The strongest networks go first, as they weigh the most on everyone else. Each one is scored
+/// against where the others currently are, or where they were already moved to, so they don't
+/// all flock to the same quiet channel. Networks that can't be moved only ever count as
+/// interference. Passes are repeated until nobody moves anymore, so every network ends up on
+/// its best candidate given the final place of all the others.
+pub struct Greedy;
+
+impl ChannelPlanner for Greedy {
+    fn plan(&self, problem: &Problem, _seed: u64) -> Vec<u8> {
+        let mut plan = problem.current();
+        let mut footprints = problem.footprints_today();
+        let order = problem.by_signal();
+
+        for _ in 0..MAX_PASSES {
+            let mut moved = false;
+
+            for idx in order.iter().copied() {
+                // staying put may still change the width a network is planned at
+                if let Some((best, footprint)) = problem
+                    .best(idx, &footprints)
+                    .map(|best| (best, problem.footprint(idx, best)))
+                    .filter(|(best, footprint)| *best != plan[idx] || *footprint != footprints[idx])
+                {
+                    footprints[idx] = footprint;
+                    plan[idx] = best;
+                    moved = true;
+                }
+            }
+
+            if !moved {
+                break;
+            }
+        }
+
+        plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::crowded;
+    use super::*;
+
+    #[test]
+    fn crowded_channel_is_spread_out() {
+        let (networks, heard) = crowded(3, 1);
+        let movable = [true; 3];
+        let problem = Problem::new_2g(&networks, &heard, &movable, &[1, 6, 11]);
+        let mut plan = Greedy.plan(&problem, 0);
+
+        assert!(problem.score(&plan).worst.is_none());
+        plan.sort_unstable();
+        assert_eq!(plan, vec![1, 6, 11]);
+    }
+}
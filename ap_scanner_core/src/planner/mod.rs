@@ -0,0 +1,688 @@
+// Channel planning from the networks seen in a scan.
+//
+// Every network in range adds its received power to the channels it overlaps, so a candidate
+// channel is scored by how much energy it would share with its neighbours. Powers are summed in
+// mW, a single -50 dBm AP outweighs a handful at -85 dBm.
+//
+// A plan may be scored over several points of a site at once. Each network is given the signal
+// it was heard with at every point, and a channel counts with the worst interference any point
+// where the network itself was heard would see on it. A single reading is a site of one point.
+//
+// How the candidates are handed out is up to a `ChannelPlanner`, the scoring is the same for all.
+mod annealing;
+mod colouring;
+mod greedy;
+mod width;
+
+pub use annealing::Annealing;
+pub use colouring::Colouring;
+pub use greedy::Greedy;
+pub use width::{advise_2g, advise_5g, advise_6g};
+
+use super::reading::{Neighbour, Rationale};
+use super::wifi::{self, Frequency, Wifi};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Display};
+
+// a typical noise floor, interference well below it makes no difference to a link
+pub const NOISE_FLOOR_DBM: f32 = -95.0;
+
+// how many of the loudest neighbours a rationale lists
+const NEIGHBOURS: usize = 3;
+
+// candidates closer than this to the suggestion, in dB, are as good as it
+const TIE_DB: f32 = 0.1;
+
+/// The 20 MHz channels a network occupies and its signal in dBm.
+type Footprint<'a> = (&'a [u8], f32);
+
+/// Interference in mW a candidate would pick up from the given footprints.
+type Interference<'a> = Box<dyn Fn(u8, &mut dyn Iterator<Item = Footprint>) -> f32 + 'a>;
+
+/// Interference in mW a network picks up from the given footprints where it is today.
+type Today<'a> = Box<dyn Fn(&Wifi, &mut dyn Iterator<Item = Footprint>) -> f32 + 'a>;
+
+/// Answers for a network and a candidate channel.
+type PerCandidate<'a, T> = Box<dyn Fn(&Wifi, u8) -> T + 'a>;
+
+/// Hands out channels to the networks of a band.
+pub trait ChannelPlanner {
+    /// Picks one of the candidates for every movable network, in the order of
+    /// `problem.networks`. The others keep their channel.
+    /// Planners that draw random numbers draw them from `seed` only, so the same problem and
+    /// seed always give the same plan.
+    fn plan(&self, problem: &Problem, seed: u64) -> Vec<u8>;
+}
+
+/// The planners to pick from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "clap", derive(clap::ArgEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    /// strongest network first, each on its least interfered channel
+    Greedy,
+    /// colours the interference graph, the most constrained network first
+    Colouring,
+    /// simulated annealing from the greedy plan, slower but better on large fleets
+    Annealing,
+}
+
+// deriving it on an enum is newer than the nightly the server is pinned to
+impl Default for Strategy {
+    fn default() -> Self {
+        Self::Greedy
+    }
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Greedy => "greedy",
+            Self::Colouring => "colouring",
+            Self::Annealing => "annealing",
+        })
+    }
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [Self::Greedy, Self::Colouring, Self::Annealing];
+
+    pub fn planner(&self) -> Box<dyn ChannelPlanner> {
+        match self {
+            Self::Greedy => Box::new(Greedy),
+            Self::Colouring => Box::new(Colouring),
+            Self::Annealing => Box::<Annealing>::default(),
+        }
+    }
+}
+
+/// Share of the energy of a 2.4 GHz channel that lands `distance` channels away.
+/// Channels are 5 MHz apart and a transmission is about 22 MHz wide, so it reaches ±4 channels.
+fn overlap_2g(distance: u8) -> f32 {
+    (22.0 - 5.0 * distance as f32).max(0.0) / 22.0
+}
+
+fn to_mw(dbm: f32) -> f32 {
+    10f32.powf(dbm / 10.0)
+}
+
+fn to_dbm(mw: f32) -> f32 {
+    10.0 * mw.log10()
+}
+
+/// Interference in dBm, `None` when there is none.
+fn to_score(mw: f32) -> Option<f32> {
+    Some(mw).filter(|mw| *mw > 0.0).map(to_dbm)
+}
+
+/// Interference in mW a 20 MHz transmission on `channel` would pick up from the given footprints.
+fn interference_2g<'a>(channel: u8, footprints: impl Iterator<Item = Footprint<'a>>) -> f32 {
+    footprints
+        .map(|(subs, signal)| {
+            // a 40 MHz AP transmits on its secondary channel as well
+            let overlap = subs
+                .iter()
+                .map(|sub| overlap_2g(sub.abs_diff(channel)))
+                .fold(0.0, f32::max);
+
+            overlap * to_mw(signal)
+        })
+        .sum()
+}
+
+/// Interference in mW a block of 20 MHz channels would pick up from the given footprints.
+/// 5 GHz channels don't overlap, a network only counts with the share of its power that falls
+/// inside the block.
+fn interference_block<'a>(block: &[u8], footprints: impl Iterator<Item = Footprint<'a>>) -> f32 {
+    footprints
+        .filter(|(subs, _)| !subs.is_empty())
+        .map(|(subs, signal)| {
+            let shared = subs.iter().filter(|sub| block.contains(sub)).count();
+
+            shared as f32 / subs.len() as f32 * to_mw(signal)
+        })
+        .sum()
+}
+
+/// Share of the power of `source` landing on the channels `victim` uses today, as plans count it.
+/// Networks of different bands don't overlap.
+pub fn overlap(victim: &Wifi, source: &Wifi) -> f32 {
+    let subs = source.sub_channels();
+    // a source heard at 0 dBm puts its overlap in mW on the victim
+    let footprint = std::iter::once((subs.as_slice(), 0.0));
+
+    match (&victim.frequency, &source.frequency) {
+        (Frequency::Freq2400MHz(_), Frequency::Freq2400MHz(_)) => {
+            interference_2g(victim.channel, footprint)
+        }
+        (Frequency::Freq5000MHz(_), Frequency::Freq5000MHz(_))
+        | (Frequency::Freq6000MHz(_), Frequency::Freq6000MHz(_)) => {
+            interference_block(&victim.sub_channels(), footprint)
+        }
+        _ => 0.0,
+    }
+}
+
+/// The 20 MHz channels a 2.4 GHz network would use with `channel` as its primary.
+/// The secondary channel of a 40 MHz one goes wherever there is room for it.
+fn footprint_2g(wifi: &Wifi, channel: u8) -> Vec<u8> {
+    match wifi.sub_channels().len() {
+        1 => vec![channel],
+        _ if channel <= 7 => vec![channel, channel + 4],
+        _ => vec![channel - 4, channel],
+    }
+}
+
+/// The footprints heard at `point`, leaving out the network at `skip` if any.
+/// Networks without a footprint aren't on the air.
+fn heard_at<'a>(
+    footprints: &'a [Vec<u8>],
+    heard: &'a [Vec<Option<f32>>],
+    point: usize,
+    skip: Option<usize>,
+) -> impl Iterator<Item = Footprint<'a>> {
+    footprints
+        .iter()
+        .zip(heard)
+        .enumerate()
+        .filter(move |(idx, _)| Some(*idx) != skip)
+        .filter(|(_, (subs, _))| !subs.is_empty())
+        .filter_map(move |(_, (subs, signals))| Some((subs.as_slice(), signals[point]?)))
+}
+
+fn points(heard: &[Vec<Option<f32>>]) -> usize {
+    heard.first().map(Vec::len).unwrap_or_default()
+}
+
+/// The networks of one band and what a plan may do with them.
+pub struct Problem<'a> {
+    pub networks: &'a [Wifi],
+    // signal in dBm of every network at each point, `None` where it wasn't heard
+    pub heard: &'a [Vec<Option<f32>>],
+    pub movable: &'a [bool],
+    pub candidates: &'a [u8],
+    interference: Interference<'a>,
+    // interference in mW a network picks up where it is today
+    today: Today<'a>,
+    // the 20 MHz channels a network would use on a candidate
+    footprint: PerCandidate<'a, Vec<u8>>,
+    // whether a candidate is where the network is today
+    is_current: PerCandidate<'a, bool>,
+}
+
+/// How a plan fares, interference in dBm, `None` when there is none.
+#[derive(Debug, Clone, Copy)]
+pub struct Score {
+    // summed over every network, each at its worst point
+    pub total: Option<f32>,
+    // of the network worst off
+    pub worst: Option<f32>,
+}
+
+impl<'a> Problem<'a> {
+    /// 2.4 GHz networks, planned on the given channels.
+    pub fn new_2g(
+        networks: &'a [Wifi],
+        heard: &'a [Vec<Option<f32>>],
+        movable: &'a [bool],
+        candidates: &'a [u8],
+    ) -> Self {
+        Self {
+            networks,
+            heard,
+            movable,
+            candidates,
+            interference: Box::new(|channel, others| interference_2g(channel, others)),
+            today: Box::new(|wifi, others| interference_2g(wifi.channel, others)),
+            footprint: Box::new(footprint_2g),
+            is_current: Box::new(|wifi, channel| wifi.channel == channel),
+        }
+    }
+
+    /// 5 GHz networks, or 6 GHz ones, planned on blocks of the given width by center channel.
+    pub fn new_5g(
+        networks: &'a [Wifi],
+        heard: &'a [Vec<Option<f32>>],
+        movable: &'a [bool],
+        mhz: u16,
+        centers: &'a [u8],
+    ) -> Self {
+        Self {
+            networks,
+            heard,
+            movable,
+            candidates: centers,
+            interference: Box::new(move |center, others| {
+                interference_block(&wifi::block_channels(center, mhz), others)
+            }),
+            today: Box::new(|wifi, others| interference_block(&wifi.sub_channels(), others)),
+            footprint: Box::new(move |_, center| wifi::block_channels(center, mhz)),
+            // staying on a block that holds the current primary channel is the least disruptive
+            is_current: Box::new(move |wifi, center| {
+                wifi::block_channels(center, mhz).contains(&wifi.channel)
+            }),
+        }
+    }
+
+    /// Where every network is today.
+    pub fn current(&self) -> Vec<u8> {
+        self.networks.iter().map(|wifi| wifi.channel).collect()
+    }
+
+    /// The movable networks, strongest first.
+    pub fn by_signal(&self) -> Vec<usize> {
+        let mut order = (0..self.networks.len())
+            .filter(|idx| self.movable[*idx])
+            .collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            self.networks[*b]
+                .signal
+                .partial_cmp(&self.networks[*a].signal)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| self.networks[*a].mac.cmp(&self.networks[*b].mac))
+        });
+
+        order
+    }
+
+    /// The 20 MHz channels the network at `idx` uses when a plan puts it on `channel`. Those
+    /// that can't be moved stay where they are today.
+    pub fn footprint(&self, idx: usize, channel: u8) -> Vec<u8> {
+        let wifi = &self.networks[idx];
+
+        match self.movable[idx] {
+            true => (self.footprint)(wifi, channel),
+            false => wifi.sub_channels(),
+        }
+    }
+
+    /// The 20 MHz channels of every network in `plan`.
+    pub fn footprints(&self, plan: &[u8]) -> Vec<Vec<u8>> {
+        plan.iter()
+            .enumerate()
+            .map(|(idx, channel)| self.footprint(idx, *channel))
+            .collect()
+    }
+
+    /// The 20 MHz channels every network uses today.
+    pub fn footprints_today(&self) -> Vec<Vec<u8>> {
+        self.networks.iter().map(Wifi::sub_channels).collect()
+    }
+
+    /// The worst interference in mW the network at `idx` picks up with the others on
+    /// `footprints`, over the points it was heard at, and the point it is worst at.
+    fn worst(
+        &self,
+        idx: usize,
+        footprints: &[Vec<u8>],
+        score: impl Fn(&mut dyn Iterator<Item = Footprint>) -> f32,
+    ) -> (f32, Option<usize>) {
+        (0..points(self.heard))
+            .filter(|point| self.heard[idx][*point].is_some())
+            .map(|point| {
+                let mut others = heard_at(footprints, self.heard, point, Some(idx));
+
+                (score(&mut others), Some(point))
+            })
+            .fold((0.0, None), |worst, other| match other.0 > worst.0 {
+                true => other,
+                false => worst,
+            })
+    }
+
+    /// Interference in mW the network at `idx` would pick up on `candidate` with the others on
+    /// `footprints`, at its worst point. Networks with an empty footprint don't count.
+    pub fn cost(&self, idx: usize, candidate: u8, footprints: &[Vec<u8>]) -> f32 {
+        self.worst(idx, footprints, |others| {
+            (self.interference)(candidate, others)
+        })
+        .0
+    }
+
+    /// The candidate with the least interference for the network at `idx`, with the others on
+    /// `footprints`. Ties keep the current channel, then go to the lowest candidate, so the same
+    /// scan always gives the same plan.
+    pub fn best(&self, idx: usize, footprints: &[Vec<u8>]) -> Option<u8> {
+        let wifi = &self.networks[idx];
+
+        self.candidates
+            .iter()
+            .map(|candidate| (*candidate, self.cost(idx, *candidate, footprints)))
+            .min_by(|(a, mw_a), (b, mw_b)| {
+                mw_a.partial_cmp(mw_b)
+                    .unwrap_or(Ordering::Equal)
+                    .then_with(|| (self.is_current)(wifi, *b).cmp(&(self.is_current)(wifi, *a)))
+                    .then_with(|| a.cmp(b))
+            })
+            .map(|(candidate, _)| candidate)
+    }
+
+    /// Interference in mW the network at `idx` picks up where it is today, with the others on
+    /// `footprints`, at its worst point.
+    fn cost_today(&self, idx: usize, footprints: &[Vec<u8>]) -> f32 {
+        self.worst(idx, footprints, |others| {
+            (self.today)(&self.networks[idx], others)
+        })
+        .0
+    }
+
+    /// Interference in mW the network at `idx` picks up at its worst point in `plan`, with
+    /// every network on `footprints`. Those that can't be moved are where they are today.
+    fn cost_in(&self, idx: usize, plan: &[u8], footprints: &[Vec<u8>]) -> f32 {
+        match self.movable[idx] {
+            true => self.cost(idx, plan[idx], footprints),
+            false => self.cost_today(idx, footprints),
+        }
+    }
+
+    /// Interference in mW every network picks up at its worst point in `plan`.
+    fn costs(&self, plan: &[u8]) -> Vec<f32> {
+        let footprints = self.footprints(plan);
+
+        (0..self.networks.len())
+            .map(|idx| self.cost_in(idx, plan, &footprints))
+            .collect()
+    }
+
+    /// The networks heard at a point where the one at `idx` is, the only ones whose cost moving
+    /// it changes.
+    fn linked(&self, idx: usize) -> Vec<usize> {
+        (0..self.networks.len())
+            .filter(|other| *other != idx)
+            .filter(|other| {
+                self.heard[idx]
+                    .iter()
+                    .zip(&self.heard[*other])
+                    .any(|(a, b)| a.is_some() && b.is_some())
+            })
+            .collect()
+    }
+
+    /// Interference in mW every network picks up at its worst point today.
+    fn costs_today(&self) -> Vec<f32> {
+        let footprints = self.footprints_today();
+
+        (0..self.networks.len())
+            .map(|idx| self.cost_today(idx, &footprints))
+            .collect()
+    }
+
+    /// Total interference plus noise in dB of `plan`, the lower the better.
+    pub fn objective(&self, plan: &[u8]) -> f32 {
+        objective(&self.costs(plan))
+    }
+
+    /// Like `objective`, for where every network is today.
+    pub fn objective_today(&self) -> f32 {
+        objective(&self.costs_today())
+    }
+
+    pub fn score(&self, plan: &[u8]) -> Score {
+        score(&self.costs(plan))
+    }
+
+    /// Like `score`, for where every network is today.
+    pub fn score_today(&self) -> Score {
+        score(&self.costs_today())
+    }
+
+    /// Why the network at `idx` was given `chosen` in `plan`.
+    ///
+    /// Today's interference is taken with every network where the scan found it, the
+    /// suggestion's with every network where the plan puts it. The improvement is the drop in
+    /// interference plus noise. The confidence is the share of the runner up candidate's
+    /// interference plus noise the suggestion avoids, so a 3 dB lead is 50% and a 10 dB one 90%.
+    /// Candidates within `TIE_DB` of the suggestion are listed as ties, the pick among them is
+    /// arbitrary and the confidence 0.
+    fn rationale(&self, idx: usize, plan: &[u8]) -> Rationale {
+        let noise = to_mw(NOISE_FLOOR_DBM);
+        let before = self.footprints_today();
+        let after = self.footprints(plan);
+        let chosen = plan[idx];
+        let wifi = &self.networks[idx];
+
+        let (now, point) = self.worst(idx, &before, |others| (self.today)(wifi, others));
+        let then = self.cost(idx, chosen, &after);
+        let others = self
+            .candidates
+            .iter()
+            .filter(|candidate| **candidate != chosen)
+            .map(|candidate| (*candidate, self.cost(idx, *candidate, &after)))
+            .collect::<Vec<_>>();
+        let runner_up = others.iter().fold(None, |best: Option<f32>, (_, mw)| {
+            Some(best.map_or(*mw, |best| best.min(*mw)))
+        });
+        let tied = others
+            .iter()
+            .filter(|(_, mw)| (to_dbm(mw + noise) - to_dbm(then + noise)).abs() < TIE_DB)
+            .map(|(candidate, _)| *candidate)
+            .collect();
+
+        let mut neighbours = point
+            .map(|point| {
+                (0..self.networks.len())
+                    .filter(|other| *other != idx)
+                    .filter_map(|other| {
+                        let signal = self.heard[other][point]?;
+                        let mw = (self.today)(
+                            wifi,
+                            &mut std::iter::once((before[other].as_slice(), signal)),
+                        );
+
+                        Some((other, signal, mw)).filter(|_| mw > 0.0)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        neighbours.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(Ordering::Equal));
+
+        Rationale {
+            current: to_score(now),
+            suggested: to_score(then),
+            neighbours: neighbours
+                .into_iter()
+                .take(NEIGHBOURS)
+                .map(|(other, signal, mw)| Neighbour {
+                    ssid: self.networks[other].ssid.clone(),
+                    mac: self.networks[other].mac.clone(),
+                    channel: self.networks[other].channel,
+                    signal,
+                    interference: to_dbm(mw),
+                })
+                .collect(),
+            improvement: to_dbm(now + noise) - to_dbm(then + noise),
+            // with a single candidate there is nothing else to pick
+            confidence: runner_up
+                .map(|runner_up| 1.0 - (then + noise) / (runner_up + noise))
+                .unwrap_or(1.0)
+                .max(0.0),
+            tied,
+        }
+    }
+}
+
+/// Total interference plus noise in dB of the interference in mW each network picks up.
+fn objective(costs: &[f32]) -> f32 {
+    let noise = to_mw(NOISE_FLOOR_DBM) * costs.len() as f32;
+
+    to_dbm(costs.iter().sum::<f32>() + noise)
+}
+
+fn score(costs: &[f32]) -> Score {
+    Score {
+        total: to_score(costs.iter().sum()),
+        worst: to_score(costs.iter().copied().fold(0.0, f32::max)),
+    }
+}
+
+/// Interference in dBm each of the given 2.4 GHz channels sees, at the point where it is worst.
+/// `heard` holds the signal of every network at each point. Channels no network overlaps are
+/// left out.
+pub fn scores_2g(
+    networks: &[Wifi],
+    heard: &[Vec<Option<f32>>],
+    channels: &[u8],
+) -> BTreeMap<u8, f32> {
+    let footprints = networks.iter().map(Wifi::sub_channels).collect::<Vec<_>>();
+
+    channels
+        .iter()
+        .map(|channel| {
+            let worst = (0..points(heard))
+                .map(|point| interference_2g(*channel, heard_at(&footprints, heard, point, None)))
+                .fold(0.0, f32::max);
+
+            (*channel, worst)
+        })
+        .filter(|(_, mw)| *mw > 0.0)
+        .map(|(channel, mw)| (channel, to_dbm(mw)))
+        .collect()
+}
+
+/// Suggests one of the `candidates` channels for every 2.4 GHz network, in the same order, along
+/// with why. Networks that aren't `movable` keep their channel.
+pub fn plan_2g(
+    networks: &[Wifi],
+    heard: &[Vec<Option<f32>>],
+    movable: &[bool],
+    candidates: &[u8],
+    planner: &dyn ChannelPlanner,
+    seed: u64,
+) -> Vec<(u8, Rationale)> {
+    let problem = Problem::new_2g(networks, heard, movable, candidates);
+    let plan = planner.plan(&problem, seed);
+
+    plan.iter()
+        .enumerate()
+        .map(|(idx, channel)| (*channel, problem.rationale(idx, &plan)))
+        .collect()
+}
+
+/// Suggests a block of the given width out of `centers` for every 5 GHz network, in the same
+/// order, along with why. Blocks are given by their center channel, as in `Suggestions5G`, 0 and
+/// no rationale when there are none.
+/// Networks that aren't `movable` keep their primary channel.
+pub fn plan_5g(
+    networks: &[Wifi],
+    heard: &[Vec<Option<f32>>],
+    movable: &[bool],
+    mhz: u16,
+    centers: &[u8],
+    planner: &dyn ChannelPlanner,
+    seed: u64,
+) -> Vec<(u8, Option<Rationale>)> {
+    plan_blocks(networks, heard, movable, mhz, centers, planner, seed)
+}
+
+/// Suggests a block of the given width out of `centers` for every 6 GHz network, the same way
+/// as `plan_5g`. The centers should be those of blocks holding a PSC, as
+/// `Domain::blocks_6g` gives, so the primary channel can stay where clients look for it.
+pub fn plan_6g(
+    networks: &[Wifi],
+    heard: &[Vec<Option<f32>>],
+    movable: &[bool],
+    mhz: u16,
+    centers: &[u8],
+    planner: &dyn ChannelPlanner,
+    seed: u64,
+) -> Vec<(u8, Option<Rationale>)> {
+    plan_blocks(networks, heard, movable, mhz, centers, planner, seed)
+}
+
+fn plan_blocks(
+    networks: &[Wifi],
+    heard: &[Vec<Option<f32>>],
+    movable: &[bool],
+    mhz: u16,
+    centers: &[u8],
+    planner: &dyn ChannelPlanner,
+    seed: u64,
+) -> Vec<(u8, Option<Rationale>)> {
+    if centers.is_empty() {
+        return networks.iter().map(|_| (0, None)).collect();
+    }
+
+    let problem = Problem::new_5g(networks, heard, movable, mhz, centers);
+    let plan = planner.plan(&problem, seed);
+
+    plan.iter()
+        .enumerate()
+        .map(|(idx, center)| (*center, Some(problem.rationale(idx, &plan))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wifi::{test_wifi, Width};
+
+    /// `count` 2.4 GHz networks all on channel 6, each heard at every one of `points` points,
+    /// louder at some than at others.
+    pub(super) fn crowded(count: usize, points: usize) -> (Vec<Wifi>, Vec<Vec<Option<f32>>>) {
+        let networks = (0..count)
+            .map(|idx| {
+                let mac = format!("02:00:00:00:00:{:02x}", idx);
+                test_wifi(&format!("Net {}", idx), &mac, 6, -45.0 - 3.0 * idx as f32)
+            })
+            .collect::<Vec<_>>();
+        let heard = networks
+            .iter()
+            .enumerate()
+            .map(|(idx, wifi)| {
+                (0..points)
+                    .map(|point| Some(wifi.signal - (7 * idx * point % 20) as f32))
+                    .collect()
+            })
+            .collect();
+
+        (networks, heard)
+    }
+
+    // an 80 MHz network on 36 and one that can't move on 44, both heard at the same point
+    fn wide_on_36() -> (Vec<Wifi>, Vec<Vec<Option<f32>>>) {
+        let mut wide = test_wifi("Office", "3c:37:86:1a:2b:3c", 36, -50.0);
+        wide.width = Width::MHz80;
+        wide.center_channel = Some(42);
+
+        let fixed = test_wifi("Neighbour", "9c:c9:eb:44:10:02", 44, -60.0);
+
+        (
+            vec![wide, fixed],
+            vec![vec![Some(-50.0)], vec![Some(-60.0)]],
+        )
+    }
+
+    #[test]
+    fn staying_is_scored_at_the_planned_width() {
+        let (networks, heard) = wide_on_36();
+        let movable = [true, false];
+        let centers = [36, 40, 44, 48];
+        let problem = Problem::new_5g(&networks, &heard, &movable, 20, &centers);
+
+        assert_eq!(problem.footprint(0, 36), vec![36]);
+        assert_eq!(problem.footprints_today()[0], vec![36, 40, 44, 48]);
+
+        // at 20 MHz staying on 36 clears 44 as well as moving to 40 does
+        let staying = problem.objective(&[36, 44]);
+        assert_eq!(staying, problem.objective(&[40, 44]));
+        assert!(staying < problem.objective(&[44, 44]));
+        assert!(problem.score(&[36, 44]).worst.is_none());
+        assert!(problem.score_today().worst.is_some());
+
+        assert_eq!(Greedy.plan(&problem, 0), vec![36, 44]);
+    }
+
+    #[test]
+    fn moving_keeps_everyone_else_in_place() {
+        let (networks, heard) = crowded(3, 1);
+        let movable = [true, false, true];
+        let problem = Problem::new_2g(&networks, &heard, &movable, &[1, 6, 11]);
+
+        for strategy in Strategy::ALL {
+            let plan = strategy.planner().plan(&problem, 7);
+
+            assert_eq!(plan[1], 6, "{}", strategy);
+            assert!(problem.score(&plan).worst.is_none(), "{}", strategy);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+// Operating width recommendations.
+//
+// Wider blocks carry more data, but there are fewer of them, they pick up more noise and they
+// overlap more neighbours. Every width is planned on its own and the throughput our networks
+// could expect on it is estimated from the Shannon capacity of the block: the signal a network is
+// heard with against the noise of the block and the interference its plan leaves it. Treating
+// interference as noise overstates it where networks take turns on the air, but it ranks the
+// widths fairly.
+//
+// A width is only recommended when it has a block no other network overlaps for each of ours,
+// unless no width does. On 2.4 GHz 40 MHz is only considered for a network alone on the band, as
+// 802.11n makes a 40 MHz network fall back to 20 MHz next to another.
+use super::{overlap_2g, to_mw, NOISE_FLOOR_DBM};
+use crate::reading::{Rationale, WidthAdvice, WidthOption};
+use crate::regulatory::Domain;
+use crate::wifi::{self, Wifi};
+
+// 1024-QAM at a 5/6 coding rate, the most 802.11ax gets out of a hertz
+const MAX_EFFICIENCY: f32 = 8.33;
+
+// a DFS block has to carry twice what one without radar rules does to be worth it: every start
+// and every radar hit costs a minute of channel availability check, ten on the weather radar
+// channels, and radar sends the network to a narrower fallback in the meantime. So 160 MHz, only
+// ever on DFS channels, wins only where 80 MHz without them is crowded
+const DFS_PENALTY: f32 = 0.5;
+
+// a narrower width this close to the best is preferred, it leaves room for the neighbours
+const NARROW_MARGIN: f32 = 0.9;
+
+/// A 5 or 6 GHz plan at a width in MHz, with or without DFS channels.
+type WidthPlan = (u16, bool, Vec<(u8, Option<Rationale>)>);
+
+/// Estimated throughput in Mbit/s of a link heard at `signal` on a block of `mhz`, with
+/// `interference` on it, both in dBm.
+fn throughput(mhz: u16, signal: f32, interference: Option<f32>) -> f32 {
+    let noise = to_mw(NOISE_FLOOR_DBM) * (mhz / 20) as f32;
+    let sinr = to_mw(signal) / (noise + interference.map_or(0.0, to_mw));
+
+    mhz as f32 * (1.0 + sinr).log2().min(MAX_EFFICIENCY)
+}
+
+/// How our networks fare on a width, each heard at its signal with the interference its plan
+/// leaves it.
+fn option(mhz: u16, dfs: bool, clean_blocks: usize, ours: &[(f32, Option<f32>)]) -> WidthOption {
+    let throughput = ours
+        .iter()
+        .map(|(signal, interference)| throughput(mhz, *signal, *interference))
+        .sum::<f32>()
+        / ours.len() as f32;
+
+    WidthOption {
+        mhz,
+        dfs,
+        clean_blocks,
+        interference: ours
+            .iter()
+            .filter_map(|(_, interference)| *interference)
+            .reduce(f32::max),
+        throughput,
+    }
+}
+
+/// Picks the width out of `options`, `None` when there are none. DFS widths are scored at
+/// `DFS_PENALTY` of their throughput.
+fn recommend(networks: usize, options: Vec<WidthOption>) -> Option<WidthAdvice> {
+    let score = |option: &WidthOption| match option.dfs {
+        true => option.throughput * DFS_PENALTY,
+        false => option.throughput,
+    };
+
+    let roomy = options
+        .iter()
+        .filter(|option| option.clean_blocks >= networks)
+        .collect::<Vec<_>>();
+    let pool = match roomy.is_empty() {
+        true => options.iter().collect(),
+        false => roomy,
+    };
+
+    let best = pool.iter().map(|option| score(option)).reduce(f32::max)?;
+    let recommended = pool
+        .into_iter()
+        .find(|option| score(option) >= best * NARROW_MARGIN)?
+        .clone();
+
+    Some(WidthAdvice {
+        networks,
+        recommended,
+        options,
+    })
+}
+
+/// The width our 2.4 GHz networks should run at, given their `plan` at 20 MHz. `None` when none
+/// of them is `owned`.
+pub fn advise_2g(
+    domain: &Domain,
+    networks: &[Wifi],
+    owned: &[bool],
+    plan: &[(u8, Rationale)],
+) -> Option<WidthAdvice> {
+    let others = networks
+        .iter()
+        .zip(owned)
+        .filter(|(_, owned)| !**owned)
+        .map(|(wifi, _)| wifi.sub_channels())
+        .collect::<Vec<_>>();
+
+    let ours = networks
+        .iter()
+        .zip(owned)
+        .zip(plan)
+        .filter(|((_, owned), _)| **owned)
+        .map(|((wifi, _), (_, why))| (wifi.signal, why.suggested))
+        .collect::<Vec<_>>();
+
+    if ours.is_empty() {
+        return None;
+    }
+
+    let clean = domain
+        .candidates_2g()
+        .into_iter()
+        .filter(|candidate| {
+            others
+                .iter()
+                .flatten()
+                .all(|sub| overlap_2g(sub.abs_diff(*candidate)) == 0.0)
+        })
+        .count();
+
+    let mut options = vec![option(20, false, clean, &ours)];
+
+    // a single 40 MHz block fits the band, for a network alone on it
+    if networks.len() == 1 {
+        options.push(option(40, false, 1, &ours));
+    }
+
+    recommend(ours.len(), options)
+}
+
+/// The width our 5 GHz networks should run at, given their plan at every width, with and
+/// without DFS channels. `None` when none of them is `owned`.
+pub fn advise_5g(
+    domain: &Domain,
+    networks: &[Wifi],
+    owned: &[bool],
+    plans: &[WidthPlan],
+) -> Option<WidthAdvice> {
+    advise_blocks(networks, owned, plans, |mhz, dfs| {
+        domain.blocks_5g(mhz, dfs)
+    })
+}
+
+/// The width our 6 GHz networks should run at, given their plan at every width on the blocks
+/// `Domain::blocks_6g` gives. The band has no DFS channels. `None` when none of them is `owned`.
+pub fn advise_6g(
+    domain: &Domain,
+    networks: &[Wifi],
+    owned: &[bool],
+    plans: &[WidthPlan],
+) -> Option<WidthAdvice> {
+    advise_blocks(networks, owned, plans, |mhz, _| domain.blocks_6g(mhz))
+}
+
+/// The width advice of a band planned on the blocks `blocks` gives for a width, with or without
+/// DFS channels.
+fn advise_blocks(
+    networks: &[Wifi],
+    owned: &[bool],
+    plans: &[WidthPlan],
+    blocks: impl Fn(u16, bool) -> Vec<u8>,
+) -> Option<WidthAdvice> {
+    let others = networks
+        .iter()
+        .zip(owned)
+        .filter(|(_, owned)| !**owned)
+        .flat_map(|(wifi, _)| wifi.sub_channels())
+        .collect::<Vec<_>>();
+
+    let options = plans
+        .iter()
+        .filter_map(|(mhz, dfs, plan)| {
+            let centers = blocks(*mhz, *dfs);
+
+            // the domain allows no block of that width
+            if centers.is_empty() {
+                return None;
+            }
+
+            let clean = centers
+                .iter()
+                .filter(|center| {
+                    wifi::block_channels(**center, *mhz)
+                        .iter()
+                        .all(|channel| !others.contains(channel))
+                })
+                .count();
+
+            let ours = networks
+                .iter()
+                .zip(owned)
+                .zip(plan)
+                .filter(|((_, owned), _)| **owned)
+                .map(|((wifi, _), (_, why))| {
+                    (wifi.signal, why.as_ref().and_then(|why| why.suggested))
+                })
+                .collect::<Vec<_>>();
+
+            match ours.is_empty() {
+                true => None,
+                false => Some(option(*mhz, *dfs, clean, &ours)),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let count = owned.iter().filter(|owned| **owned).count();
+
+    recommend(count, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{plan_6g, Greedy};
+    use super::*;
+    use crate::wifi::{test_wifi, Frequency};
+
+    fn options(interference_80: Option<f32>) -> Vec<WidthOption> {
+        let ours = [(-55.0, None)];
+        let crowded = [(-55.0, interference_80)];
+
+        vec![
+            option(20, false, 9, &ours),
+            option(40, false, 4, &ours),
+            option(80, false, 2, &crowded),
+            option(80, true, 6, &ours),
+            option(160, true, 2, &ours),
+        ]
+    }
+
+    #[test]
+    fn clean_site_stays_off_dfs() {
+        let advice = recommend(1, options(None)).unwrap();
+
+        assert_eq!(advice.recommended.mhz, 80);
+        assert!(!advice.recommended.dfs);
+    }
+
+    #[test]
+    fn six_ghz_is_advised_on_psc_blocks() {
+        let domain = Domain::for_country("US").unwrap();
+        let mut ours = test_wifi("Office 6G", "3c:37:86:1a:2b:3e", 5, -55.0);
+        ours.frequency = Frequency::Freq6000MHz(5975);
+        let networks = [ours];
+        let heard = [vec![Some(-55.0)]];
+
+        let plans = [20, 40, 80, 160, 320].map(|mhz| {
+            let centers = domain.blocks_6g(mhz);
+            let plan = plan_6g(&networks, &heard, &[true], mhz, &centers, &Greedy, 0);
+
+            (mhz, false, plan)
+        });
+        let advice = advise_6g(domain, &networks, &[true], &plans).unwrap();
+
+        assert_eq!(advice.options.len(), 5);
+        assert!(advice.options.iter().all(|option| !option.dfs));
+        assert!(advice.options.iter().all(|option| option.clean_blocks > 0));
+    }
+
+    #[test]
+    fn dfs_160_only_beats_a_crowded_80() {
+        let advice = recommend(1, options(Some(-60.0))).unwrap();
+
+        assert_eq!(advice.recommended.mhz, 160);
+        assert!(advice.recommended.dfs);
+    }
+}
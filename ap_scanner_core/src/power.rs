@@ -0,0 +1,202 @@
+// Transmit power advice for our own radios.
+//
+// Channels alone can't keep a dense deployment quiet. An AP louder than it needs to be reaches
+// past its clients into the cells of every network sharing its channel. A radio serves the
+// points where it is the loudest of ours on its band, and needs to be heard at `COVERAGE_DBM` at
+// each of them. Whatever it is heard with above that, less some headroom, is power it can give
+// up when other networks share its channel. A served point below it is a coverage gap, which
+// more power only closes up to the regulatory limit.
+//
+// Scans don't tell how loud an AP transmits. It is taken to run at the most its Country element
+// allows on its channel, or at the regulatory limit when it advertises none, as APs ship at full
+// power. The advice is about the channels in use today. A radio heard at a single point, as in
+// any one scan, may just have been next to the scanner, it is turned down by `SINGLE_POINT_CUT_DB`
+// at most until more points are surveyed.
+use super::reading::Power;
+use super::regulatory::{Band, Domain};
+use super::wifi::Wifi;
+use std::collections::BTreeMap;
+
+// signal a client needs at the edge of a cell for data and voice
+pub const COVERAGE_DBM: f32 = -67.0;
+
+// kept above the coverage target when turning down, for clients worse placed than the scanner
+const HEADROOM_DB: f32 = 3.0;
+
+// radios aren't turned down below this EIRP
+const MIN_EIRP_DBM: i8 = 5;
+
+// the most a radio heard at a single point is turned down by
+pub const SINGLE_POINT_CUT_DB: i8 = 6;
+
+/// Whether two networks overlap on the air. 2.4 GHz channels reach 4 channels to each side.
+fn shares_channel(a: &Wifi, b: &Wifi) -> bool {
+    if a.band() != b.band() {
+        return false;
+    }
+
+    let (a_subs, b_subs) = (a.sub_channels(), b.sub_channels());
+
+    match a.band() {
+        Band::Ghz2_4 => a_subs
+            .iter()
+            .any(|a| b_subs.iter().any(|b| a.abs_diff(*b) <= 4)),
+        Band::Ghz5 | Band::Ghz6 => a_subs.iter().any(|a| b_subs.contains(a)),
+    }
+}
+
+/// The EIRP a radio is taken to run at and the most it may, `None` off the channels of the
+/// domain.
+fn eirp(wifi: &Wifi, domain: &Domain) -> Option<(i8, i8)> {
+    let center = wifi.center_channel.unwrap_or(wifi.channel);
+    let limit = domain.max_eirp(wifi.band(), center, wifi.width.mhz())?;
+
+    let advertised = wifi.country.as_ref().and_then(|country| {
+        country
+            .power_limits
+            .iter()
+            .find(|power| (power.first_channel..=power.last_channel).contains(&wifi.channel))
+            .map(|power| power.max_power_dbm)
+    });
+
+    Some((advertised.map_or(limit, |power| power.min(limit)), limit))
+}
+
+/// Transmit power advice for every `owned` network, by BSSID. `networks` holds every network
+/// with the signal it was heard with at each point.
+pub fn advise(
+    networks: &[(Wifi, Vec<Option<f32>>)],
+    owned: &[bool],
+    domain: &Domain,
+) -> BTreeMap<String, Power> {
+    networks
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| owned[*idx])
+        .filter_map(|(idx, (wifi, signals))| {
+            let (current, limit) = eirp(wifi, domain)?;
+
+            // where none of ours on the band is louder
+            let served = signals
+                .iter()
+                .enumerate()
+                .filter_map(|(point, signal)| {
+                    let signal = (*signal)?;
+                    let louder = networks.iter().zip(owned).enumerate().any(
+                        |(other, ((them, heard), owned))| {
+                            other != idx
+                                && *owned
+                                && them.band() == wifi.band()
+                                && matches!(heard[point], Some(theirs) if theirs > signal)
+                        },
+                    );
+
+                    match louder {
+                        true => None,
+                        false => Some(signal),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // another of ours is louder everywhere, it can only get in their way
+            let weakest = match served.is_empty() {
+                true => signals.iter().flatten().copied().reduce(f32::min)?,
+                false => served.iter().copied().reduce(f32::min)?,
+            };
+            let gaps = served
+                .iter()
+                .filter(|signal| **signal < COVERAGE_DBM)
+                .count();
+
+            // heard at a point where it is heard as well
+            let neighbours = networks
+                .iter()
+                .enumerate()
+                .filter(|(other, (them, heard))| {
+                    *other != idx
+                        && shares_channel(wifi, them)
+                        && signals
+                            .iter()
+                            .zip(heard)
+                            .any(|(ours, theirs)| ours.is_some() && theirs.is_some())
+                })
+                .count();
+
+            let single_point = signals.iter().flatten().count() < 2;
+
+            let change = if gaps > 0 {
+                (COVERAGE_DBM - weakest).ceil() as i8
+            } else if neighbours > 0 {
+                let cut = (weakest - COVERAGE_DBM - HEADROOM_DB).floor().max(0.0) as i8;
+                match single_point {
+                    true => -cut.min(SINGLE_POINT_CUT_DB),
+                    false => -cut,
+                }
+            } else {
+                0
+            };
+
+            let suggested = current
+                .saturating_add(change)
+                .min(limit)
+                .max(MIN_EIRP_DBM.min(current));
+
+            Some((
+                wifi.mac.clone(),
+                Power {
+                    current,
+                    suggested,
+                    limit,
+                    weakest,
+                    gaps,
+                    points: served.len(),
+                    neighbours,
+                    single_point,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wifi::{test_wifi, Width};
+
+    // Office on channel 6, its neighbour on 11 at 40 MHz down to 7, at every point given for
+    // Office
+    fn office(points: &[f32]) -> Power {
+        let mut neighbour = test_wifi("", "9c:c9:eb:44:10:02", 11, -81.0);
+        neighbour.width = Width::MHz40;
+        neighbour.center_channel = Some(9);
+
+        let networks = vec![
+            (
+                test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -47.0),
+                points.iter().copied().map(Some).collect(),
+            ),
+            (neighbour, vec![Some(-81.0); points.len()]),
+        ];
+
+        let domain = Domain::for_country("PT").unwrap();
+        advise(&networks, &[true, false], domain)
+            .remove("3c:37:86:1a:2b:3d")
+            .unwrap()
+    }
+
+    #[test]
+    fn single_point_cut_is_held() {
+        let power = office(&[-47.0]);
+
+        assert!(power.single_point);
+        assert_eq!(power.suggested, power.current - SINGLE_POINT_CUT_DB);
+    }
+
+    #[test]
+    fn surveyed_cut_goes_further() {
+        let power = office(&[-47.0, -50.0]);
+
+        assert!(!power.single_point);
+        assert_eq!(power.suggested, 6);
+    }
+}
@@ -0,0 +1,625 @@
+use super::fleet::Fleet;
+use super::planner::{self, ChannelPlanner};
+use super::power::{self, COVERAGE_DBM, SINGLE_POINT_CUT_DB};
+use super::regulatory::{Band, Domain};
+use super::survey::ChannelSurvey;
+use super::wifi::Wifi;
+use anyhow::Context;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Reading {
+    // time since epoch in milliseconds
+    pub timestamp: u128,
+    // identifier of the local this measure was taken
+    pub local: String,
+    // Map representing the distribution of 2.4 GHz AP
+    pub wifi_2_4_ghz: BTreeMap<u8, Vec<(Wifi, Suggestion)>>,
+    // Map representing the distribution of 5 GHz AP
+    pub wifi_5_ghz: BTreeMap<u8, Vec<(Wifi, Suggestion)>>,
+    // Map representing the distribution of 6 GHz AP, channel numbers restart at 1 on this band
+    #[serde(default)]
+    pub wifi_6_ghz: BTreeMap<u8, Vec<(Wifi, Suggestion)>>,
+    // interference in dBm each 2.4 GHz channel sees, channels nothing overlaps are left out
+    #[serde(default)]
+    pub interference_2_4_ghz: BTreeMap<u8, f32>,
+    // country whose rules the suggestions follow, the world rules are used when unknown
+    #[serde(default)]
+    pub country: Option<String>,
+    // survey data of every channel the driver reported, by frequency in MHz
+    #[serde(default)]
+    pub survey: BTreeMap<u16, ChannelSurvey>,
+    // why each planned network got its suggestion, by BSSID
+    #[serde(default)]
+    pub rationale: BTreeMap<String, Rationale>,
+    // every random choice behind the suggestions is drawn from it, planning the same networks
+    // with the same seed gives the same suggestions
+    #[serde(default)]
+    pub seed: u64,
+    // the width our 2.4 GHz networks should run at, `None` without any of ours on the band
+    #[serde(default)]
+    pub width_2_4_ghz: Option<WidthAdvice>,
+    // the width our 5 GHz networks should run at, `None` without any of ours on the band
+    #[serde(default)]
+    pub width_5_ghz: Option<WidthAdvice>,
+    // the width our 6 GHz networks should run at, `None` without any of ours on the band
+    #[serde(default)]
+    pub width_6_ghz: Option<WidthAdvice>,
+    // transmit power advice for each of our networks, by BSSID
+    #[serde(default)]
+    pub power: BTreeMap<String, Power>,
+}
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+pub enum Suggestion {
+    Suggestion2g(u8),
+    Suggestion5g(Suggestions5G),
+    Suggestion6g(Suggestions6G),
+    // not one of ours, planned around but left where it is
+    Fixed,
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let out = match self {
+            Suggestion::Suggestion2g(v) => *v,
+            Suggestion::Suggestion5g(v) => v.ndfs_20,
+            Suggestion::Suggestion6g(v) => v.psc_20,
+            Suggestion::Fixed => return f.write_str("fixed"),
+        };
+
+        f.write_str(&format!("{}", out))
+    }
+}
+
+/// Transmit power advice for one of our radios, EIRP in dBm.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Power {
+    // what it is taken to run at today, scans don't tell
+    pub current: i8,
+    pub suggested: i8,
+    // the most allowed on its channel
+    pub limit: i8,
+    // signal at the weakest of the points it serves
+    pub weakest: f32,
+    // points it serves where it is too weak for clients, out of `points`
+    pub gaps: usize,
+    pub points: usize,
+    // networks sharing its channel where it is heard
+    pub neighbours: usize,
+    // heard at one point only, the advice is a guess
+    #[serde(default)]
+    pub single_point: bool,
+}
+
+impl Display for Power {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let change = self.suggested - self.current;
+
+        match change {
+            0 => writeln!(
+                f,
+                "Keep transmit power at {} dBm EIRP, as assumed today.",
+                self.current
+            )?,
+            _ => writeln!(
+                f,
+                "{} transmit power by {} dB to {} dBm EIRP, from an assumed {} dBm.",
+                if change < 0 { "Lower" } else { "Raise" },
+                change.abs(),
+                self.suggested,
+                self.current
+            )?,
+        }
+
+        write!(
+            f,
+            "Heard at {:.0} dBm where it is weakest, {} on its channel.",
+            self.weakest,
+            count(self.neighbours, "other network")
+        )?;
+
+        if self.gaps > 0 {
+            write!(
+                f,
+                "\nCoverage gap risk: below {:.0} dBm at {} of {} points it serves",
+                COVERAGE_DBM, self.gaps, self.points
+            )?;
+
+            match self.suggested >= self.limit {
+                true => write!(
+                    f,
+                    ", even at the {} dBm limit it may take another AP.",
+                    self.limit
+                )?,
+                false => write!(f, ".")?,
+            }
+        }
+
+        if self.single_point {
+            write!(
+                f,
+                "\nLow confidence, heard at a single point only: survey more before acting on it, \
+                 cuts are held to {} dB until then.",
+                SINGLE_POINT_CUT_DB
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Blocks are given by their center channel, 0 where the regulatory domain allows none.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Suggestions5G {
+    pub ndfs_20: u8,
+    pub dfs_20: u8,
+    pub ndfs_40: u8,
+    pub dfs_40: u8,
+    pub ndfs_80: u8,
+    pub dfs_80: u8,
+    pub dfs_160: u8,
+}
+
+/// 6 GHz clients only actively scan the preferred scanning channels (PSC), one every 80 MHz.
+/// Every suggestion keeps its primary channel on a PSC, wider blocks are given by their center,
+/// 0 where the regulatory domain allows none.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Suggestions6G {
+    pub psc_20: u8,
+    pub psc_40: u8,
+    pub psc_80: u8,
+    pub psc_160: u8,
+    pub psc_320: u8,
+}
+
+/// Why a suggestion was made. Interference is in dBm at the worst point the network was heard
+/// at, `None` when nothing interferes. 5 and 6 GHz networks are explained at their current
+/// width.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Rationale {
+    // on the channel the network uses today
+    pub current: Option<f32>,
+    // on the suggested channel, once the other networks are moved as well
+    pub suggested: Option<f32>,
+    // the networks weighing the most on the current channel, loudest first
+    pub neighbours: Vec<Neighbour>,
+    // drop in interference plus noise in dB, negative when the network gives way to others
+    pub improvement: f32,
+    // between 0 and 1, how clearly the suggestion beats the next best channel
+    pub confidence: f32,
+    // the other channels that do just as well, any of them would do
+    #[serde(default)]
+    pub tied: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Neighbour {
+    pub ssid: String,
+    pub mac: String,
+    pub channel: u8,
+    // as heard at the worst point
+    pub signal: f32,
+    // the part of the interference it causes, in dBm
+    pub interference: f32,
+}
+
+impl Display for Rationale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let score = |dbm: Option<f32>| match dbm {
+            Some(dbm) => format!("{:.1} dBm", dbm),
+            None => "none".to_string(),
+        };
+
+        writeln!(
+            f,
+            "Interference now: {}, after the change: {}.",
+            score(self.current),
+            score(self.suggested)
+        )?;
+        match self.tied.as_slice() {
+            [] => write!(
+                f,
+                "Expected improvement: {:.1} dB, confidence: {:.0}%.",
+                self.improvement,
+                self.confidence * 100.0
+            )?,
+            tied => write!(
+                f,
+                "Expected improvement: {:.1} dB, tied with {} {}, any of them will do.",
+                self.improvement,
+                match tied.len() {
+                    1 => "channel",
+                    _ => "channels",
+                },
+                tied.iter().join(", ")
+            )?,
+        }
+
+        if !self.neighbours.is_empty() {
+            write!(f, "\nLoudest neighbours:")?;
+        }
+
+        for neighbour in &self.neighbours {
+            write!(
+                f,
+                "\n\t{}, {} on channel {}, heard at {:.0} dBm: {:.1} dBm",
+                neighbour.ssid,
+                neighbour.mac,
+                neighbour.channel,
+                neighbour.signal,
+                neighbour.interference
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How our networks would fare at one operating width, each planned on it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WidthOption {
+    pub mhz: u16,
+    // whether DFS channels may be used
+    pub dfs: bool,
+    // blocks of that width no other network overlaps
+    pub clean_blocks: usize,
+    // worst interference one of our networks would pick up, in dBm
+    pub interference: Option<f32>,
+    // estimated for each of our networks on average, in Mbit/s
+    pub throughput: f32,
+}
+
+impl WidthOption {
+    pub fn label(&self) -> String {
+        match self.dfs {
+            true => format!("DFS {}MHz", self.mhz),
+            false => format!("{}MHz", self.mhz),
+        }
+    }
+}
+
+/// The width our networks of a band should run at, with every width that was considered,
+/// narrowest first.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WidthAdvice {
+    // how many of our networks are on the band
+    pub networks: usize,
+    pub recommended: WidthOption,
+    pub options: Vec<WidthOption>,
+}
+
+impl Display for WidthAdvice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let recommended = &self.recommended;
+        let fastest = self.options.iter().fold(recommended, |best, option| {
+            match option.throughput > best.throughput {
+                true => option,
+                false => best,
+            }
+        });
+
+        writeln!(
+            f,
+            "Use {}: about {:.0} Mbit/s per network, {} for {} of ours.",
+            recommended.label(),
+            recommended.throughput,
+            count(recommended.clean_blocks, "clean block"),
+            count(self.networks, "network")
+        )?;
+
+        if fastest.mhz != recommended.mhz || fastest.dfs != recommended.dfs {
+            let reason = if fastest.clean_blocks < self.networks
+                && recommended.clean_blocks >= self.networks
+            {
+                format!("only has {}", count(fastest.clean_blocks, "clean block"))
+            } else if fastest.dfs && !recommended.dfs {
+                "uses DFS channels radar may force it off of".to_string()
+            } else {
+                "isn't worth the room it takes from the neighbours".to_string()
+            };
+
+            writeln!(
+                f,
+                "{} would carry about {:.0} Mbit/s but {}.",
+                fastest.label(),
+                fastest.throughput,
+                reason
+            )?;
+        } else if self
+            .options
+            .iter()
+            .any(|option| option.mhz > recommended.mhz)
+        {
+            writeln!(
+                f,
+                "Wider blocks lose more to interference and noise than they gain in bandwidth."
+            )?;
+        }
+
+        write!(f, "Per width:")?;
+
+        for option in &self.options {
+            write!(
+                f,
+                "\n\t{}: {}, about {:.0} Mbit/s, interference {}",
+                option.label(),
+                count(option.clean_blocks, "clean block"),
+                option.throughput,
+                match option.interference {
+                    Some(dbm) => format!("up to {:.1} dBm", dbm),
+                    None => "none".to_string(),
+                }
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// `count` of `noun`, made plural unless there is one.
+fn count(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        count => format!("{} {}s", count, noun),
+    }
+}
+
+impl Reading {
+    /// Plans the given networks along with the signal each was heard with at every point of the
+    /// local, the worst point deciding. A plain reading has a single point.
+    pub fn plan(
+        local: String,
+        networks: Vec<(Wifi, Vec<Option<f32>>)>,
+        country: Option<String>,
+        survey: BTreeMap<u16, ChannelSurvey>,
+        fleet: &Fleet,
+        planner: &dyn ChannelPlanner,
+        seed: u64,
+    ) -> anyhow::Result<Self> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .with_context(|| anyhow::anyhow!("Failed to get timestamp."))?
+            .as_millis();
+
+        let domain = Domain::resolve(country.as_deref());
+
+        let owned = networks
+            .iter()
+            .map(|(wifi, _)| fleet.owns(wifi))
+            .collect_vec();
+        let power = power::advise(&networks, &owned, domain);
+
+        let (left, right): (Vec<_>, Vec<_>) =
+            networks.into_iter().partition(|(wifi, _)| wifi.is_2_4g());
+        let (right, six): (Vec<_>, Vec<_>) = right.into_iter().partition(|(wifi, _)| wifi.is_5g());
+
+        // 2.4 GHz, sorted so the plan doesn't depend on the order networks were reported in
+        let (left, heard): (Vec<_>, Vec<_>) = left
+            .into_iter()
+            .sorted_by(|a, b| a.0.mac.cmp(&b.0.mac))
+            .unzip();
+
+        let interference_2_4_ghz =
+            planner::scores_2g(&left, &heard, &domain.channels(Band::Ghz2_4));
+        let owned = left.iter().map(|wifi| fleet.owns(wifi)).collect_vec();
+        let plan_2g = planner::plan_2g(
+            &left,
+            &heard,
+            &owned,
+            &domain.candidates_2g(),
+            planner,
+            seed,
+        );
+
+        let width_2_4_ghz = planner::advise_2g(domain, &left, &owned, &plan_2g);
+
+        let mut rationale = BTreeMap::new();
+
+        let wifi_2_4_ghz = left
+            .into_iter()
+            .zip(owned.into_iter().zip(plan_2g))
+            .map(|(wifi, (owned, (channel, why)))| match owned {
+                true => {
+                    rationale.insert(wifi.mac.clone(), why);
+                    (wifi, Suggestion::Suggestion2g(channel))
+                }
+                false => (wifi, Suggestion::Fixed),
+            })
+            .into_group_map_by(|tuple| tuple.0.channel)
+            .into_iter()
+            .collect();
+
+        // 5 GHz, every width is planned on its own, with and without DFS channels
+        //
+        // https://www.smallnetbuilder.com/wireless/wireless-features/33210-160-mhz-wi-fi-channels-friend-or-foe?start=1
+        // https://www.tech21century.com/best-wifi-channels-for-your-router/
+        // https://www.ekahau.com/blog/channel-planning-best-practices-for-better-wi-fi/
+        let (right, heard): (Vec<_>, Vec<_>) = right
+            .into_iter()
+            .sorted_by(|a, b| a.0.mac.cmp(&b.0.mac))
+            .unzip();
+
+        let owned = right.iter().map(|wifi| fleet.owns(wifi)).collect_vec();
+
+        let plans = [
+            (20, false),
+            (20, true),
+            (40, false),
+            (40, true),
+            (80, false),
+            (80, true),
+            (160, true),
+        ]
+        .map(|(mhz, dfs)| {
+            let plan = planner::plan_5g(
+                &right,
+                &heard,
+                &owned,
+                mhz,
+                &domain.blocks_5g(mhz, dfs),
+                planner,
+                seed,
+            );
+
+            (mhz, dfs, plan)
+        });
+
+        let width_5_ghz = planner::advise_5g(domain, &right, &owned, &plans);
+
+        let [mut ndfs20, mut dfs20, mut ndfs40, mut dfs40, mut ndfs80, mut dfs80, mut dfs160] =
+            plans.map(|(_, _, plan)| plan.into_iter());
+
+        let wifi_5_ghz = right
+            .into_iter()
+            .zip(owned)
+            .map(|(wifi, owned)| {
+                let (ndfs_20, ndfs_20_why) = ndfs20.next().expect("ndfs20");
+                let (dfs_20, dfs_20_why) = dfs20.next().expect("dfs20");
+                let (ndfs_40, ndfs_40_why) = ndfs40.next().expect("ndfs40");
+                let (dfs_40, dfs_40_why) = dfs40.next().expect("dfs40");
+                let (ndfs_80, ndfs_80_why) = ndfs80.next().expect("ndfs80");
+                let (dfs_80, dfs_80_why) = dfs80.next().expect("dfs80");
+                let (dfs_160, dfs_160_why) = dfs160.next().expect("dfs160");
+
+                if !owned {
+                    return (wifi, Suggestion::Fixed);
+                }
+
+                // explained at the width the network uses today, without DFS channels if it can
+                let why = match wifi.width.mhz() {
+                    20 => ndfs_20_why.or(dfs_20_why),
+                    40 => ndfs_40_why.or(dfs_40_why),
+                    80 => ndfs_80_why.or(dfs_80_why),
+                    _ => dfs_160_why,
+                };
+
+                if let Some(why) = why {
+                    rationale.insert(wifi.mac.clone(), why);
+                }
+
+                let suggestion = Suggestion::Suggestion5g(Suggestions5G {
+                    ndfs_20,
+                    dfs_20,
+                    ndfs_40,
+                    dfs_40,
+                    ndfs_80,
+                    dfs_80,
+                    dfs_160,
+                });
+
+                (wifi, suggestion)
+            })
+            .into_group_map_by(|x| x.0.channel)
+            .into_iter()
+            .collect();
+
+        // 6 GHz, every width on its own, on the blocks that keep the primary channel on a PSC
+        let (six, heard): (Vec<_>, Vec<_>) = six
+            .into_iter()
+            .sorted_by(|a, b| a.0.mac.cmp(&b.0.mac))
+            .unzip();
+
+        let owned = six.iter().map(|wifi| fleet.owns(wifi)).collect_vec();
+
+        let plans = [20, 40, 80, 160, 320].map(|mhz| {
+            let plan = planner::plan_6g(
+                &six,
+                &heard,
+                &owned,
+                mhz,
+                &domain.blocks_6g(mhz),
+                planner,
+                seed,
+            );
+
+            (mhz, false, plan)
+        });
+
+        let width_6_ghz = planner::advise_6g(domain, &six, &owned, &plans);
+
+        let [mut psc20, mut psc40, mut psc80, mut psc160, mut psc320] =
+            plans.map(|(_, _, plan)| plan.into_iter());
+
+        let wifi_6_ghz = six
+            .into_iter()
+            .zip(owned)
+            .map(|(wifi, owned)| {
+                let (psc_20, psc_20_why) = psc20.next().expect("psc20");
+                let (psc_40, psc_40_why) = psc40.next().expect("psc40");
+                let (psc_80, psc_80_why) = psc80.next().expect("psc80");
+                let (psc_160, psc_160_why) = psc160.next().expect("psc160");
+                let (psc_320, psc_320_why) = psc320.next().expect("psc320");
+
+                if !owned {
+                    return (wifi, Suggestion::Fixed);
+                }
+
+                // explained at the width the network uses today
+                let why = match wifi.width.mhz() {
+                    20 => psc_20_why,
+                    40 => psc_40_why,
+                    80 => psc_80_why,
+                    160 => psc_160_why,
+                    _ => psc_320_why,
+                };
+
+                if let Some(why) = why {
+                    rationale.insert(wifi.mac.clone(), why);
+                }
+
+                let suggestion = Suggestion::Suggestion6g(Suggestions6G {
+                    psc_20,
+                    psc_40,
+                    psc_80,
+                    psc_160,
+                    psc_320,
+                });
+
+                (wifi, suggestion)
+            })
+            .into_group_map_by(|x| x.0.channel)
+            .into_iter()
+            .collect();
+
+        Ok(Self {
+            timestamp,
+            local,
+            wifi_2_4_ghz,
+            wifi_5_ghz,
+            wifi_6_ghz,
+            interference_2_4_ghz,
+            country,
+            survey,
+            rationale,
+            seed,
+            width_2_4_ghz,
+            width_5_ghz,
+            width_6_ghz,
+            power,
+        })
+    }
+
+    pub fn deserialize(path: String) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(&path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+
+        serde_json::from_reader(file)
+            .with_context(|| format!("Failed to load reading from {:?}", path))
+    }
+
+    pub fn serialize(&self, path: String) -> anyhow::Result<()> {
+        let file = std::fs::File::create(&path)
+            .with_context(|| format!("Failed to open file {:?}", path))?;
+
+        serde_json::to_writer_pretty(file, self)
+            .with_context(|| format!("Failed to save reading to {:?}", path))
+    }
+}
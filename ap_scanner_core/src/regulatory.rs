@@ -0,0 +1,327 @@
+// Regulatory domains, which channels may be used where and how loud.
+//
+// The tables roughly follow the Linux wireless-regdb for indoor use. Limits are the maximum EIRP
+// of a 20 MHz channel in dBm. Countries without a table of their own get the conservative world
+// rules.
+use super::wifi::{self, Wifi};
+use itertools::Itertools;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Band {
+    Ghz2_4,
+    Ghz5,
+    Ghz6,
+}
+
+impl Band {
+    /// Distance between two adjacent 20 MHz channel numbers.
+    fn step(&self) -> u8 {
+        match self {
+            Self::Ghz2_4 => 1,
+            Self::Ghz5 | Self::Ghz6 => 4,
+        }
+    }
+}
+
+/// A run of channels sharing the same rules.
+#[derive(Debug)]
+pub struct Rule {
+    pub band: Band,
+    pub first: u8,
+    pub last: u8,
+    // radar detection is required before transmitting
+    pub dfs: bool,
+    pub max_eirp: i8,
+    // 802.11b only, like channel 14 in Japan
+    pub dsss_only: bool,
+    // only preferred scanning channels may be primary, the rest of a block may be any channel
+    pub psc_only: bool,
+}
+
+impl Rule {
+    const fn new(band: Band, first: u8, last: u8, max_eirp: i8) -> Self {
+        Self {
+            band,
+            first,
+            last,
+            dfs: false,
+            max_eirp,
+            dsss_only: false,
+            psc_only: false,
+        }
+    }
+
+    const fn dfs(self) -> Self {
+        Self { dfs: true, ..self }
+    }
+
+    const fn dsss_only(self) -> Self {
+        Self {
+            dsss_only: true,
+            ..self
+        }
+    }
+
+    const fn psc_only(self) -> Self {
+        Self {
+            psc_only: true,
+            ..self
+        }
+    }
+
+    fn contains(&self, band: Band, channel: u8) -> bool {
+        self.band == band
+            && (self.first..=self.last).contains(&channel)
+            && (channel - self.first) % band.step() == 0
+    }
+}
+
+#[derive(Debug)]
+pub struct Domain {
+    pub name: &'static str,
+    countries: &'static [&'static str],
+    rules: &'static [Rule],
+}
+
+const FCC: Domain = Domain {
+    name: "FCC",
+    countries: &["US", "CA", "PR", "GU", "VI", "AS", "MP"],
+    rules: &[
+        Rule::new(Band::Ghz2_4, 1, 11, 30),
+        Rule::new(Band::Ghz5, 36, 48, 23),
+        Rule::new(Band::Ghz5, 52, 64, 24).dfs(),
+        Rule::new(Band::Ghz5, 100, 144, 24).dfs(),
+        Rule::new(Band::Ghz5, 149, 165, 30),
+        Rule::new(Band::Ghz6, 1, 233, 18),
+    ],
+};
+
+const ETSI: Domain = Domain {
+    name: "ETSI",
+    countries: &[
+        "EU", "AT", "BE", "BG", "HR", "CY", "CZ", "DK", "EE", "FI", "FR", "DE", "GR", "HU", "IE",
+        "IT", "LV", "LT", "LU", "MT", "NL", "PL", "PT", "RO", "SK", "SI", "ES", "SE", "GB", "UK",
+        "CH", "NO", "IS", "LI",
+    ],
+    rules: &[
+        Rule::new(Band::Ghz2_4, 1, 13, 20),
+        Rule::new(Band::Ghz5, 36, 48, 23),
+        Rule::new(Band::Ghz5, 52, 64, 20).dfs(),
+        Rule::new(Band::Ghz5, 100, 140, 27).dfs(),
+        // short range devices, allowed but barely
+        Rule::new(Band::Ghz5, 149, 165, 14),
+        Rule::new(Band::Ghz6, 1, 93, 23),
+    ],
+};
+
+const MIC: Domain = Domain {
+    name: "MIC",
+    countries: &["JP"],
+    rules: &[
+        Rule::new(Band::Ghz2_4, 1, 13, 20),
+        Rule::new(Band::Ghz2_4, 14, 14, 20).dsss_only(),
+        Rule::new(Band::Ghz5, 36, 48, 20),
+        Rule::new(Band::Ghz5, 52, 64, 20).dfs(),
+        Rule::new(Band::Ghz5, 100, 140, 23).dfs(),
+        Rule::new(Band::Ghz6, 1, 93, 23),
+    ],
+};
+
+const SRRC: Domain = Domain {
+    name: "SRRC",
+    countries: &["CN"],
+    rules: &[
+        Rule::new(Band::Ghz2_4, 1, 13, 20),
+        Rule::new(Band::Ghz5, 36, 48, 23),
+        Rule::new(Band::Ghz5, 52, 64, 23).dfs(),
+        Rule::new(Band::Ghz5, 149, 165, 33),
+    ],
+};
+
+/// What's allowed just about everywhere.
+pub const WORLD: Domain = Domain {
+    name: "World",
+    countries: &["00"],
+    rules: &[
+        Rule::new(Band::Ghz2_4, 1, 11, 20),
+        Rule::new(Band::Ghz5, 36, 48, 20),
+        Rule::new(Band::Ghz5, 52, 64, 20).dfs(),
+        Rule::new(Band::Ghz5, 100, 144, 20).dfs(),
+        Rule::new(Band::Ghz5, 149, 165, 20),
+        // the lower part of 6 GHz is open indoors in most countries that open it at all
+        Rule::new(Band::Ghz6, 1, 93, 14).psc_only(),
+    ],
+};
+
+const DOMAINS: &[Domain] = &[FCC, ETSI, MIC, SRRC, WORLD];
+
+// every aligned block of each band by width, given by its center channel
+const BLOCKS_5G: &[(u16, &[u8])] = &[
+    (
+        40,
+        &[38, 46, 54, 62, 102, 110, 118, 126, 134, 142, 151, 159],
+    ),
+    (80, &[42, 58, 106, 122, 138, 155]),
+    (160, &[50, 114]),
+];
+
+/// Whether a 6 GHz channel is a preferred scanning channel, every fourth one from 5.
+pub fn is_psc(channel: u8) -> bool {
+    channel % 16 == 5
+}
+
+/// Center of the aligned 5 GHz block of the given width holding `channel`, `None` when no block
+/// of that width does.
+pub fn block_5g(channel: u8, mhz: u16) -> Option<u8> {
+    match mhz {
+        20 => Some(channel),
+        _ => BLOCKS_5G
+            .iter()
+            .find(|(width, _)| *width == mhz)?
+            .1
+            .iter()
+            .copied()
+            .find(|center| wifi::block_channels(*center, mhz).contains(&channel)),
+    }
+}
+
+impl Domain {
+    /// The domain of a two letter country code, `None` if it has no table of its own.
+    pub fn for_country(code: &str) -> Option<&'static Domain> {
+        let code = code.trim().to_uppercase();
+
+        DOMAINS
+            .iter()
+            .find(|domain| domain.countries.contains(&code.as_str()))
+    }
+
+    /// The domain of a country code, the world rules when unknown or missing.
+    pub fn resolve(code: Option<&str>) -> &'static Domain {
+        code.and_then(Self::for_country).unwrap_or(&WORLD)
+    }
+
+    fn rule(&self, band: Band, channel: u8) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.contains(band, channel))
+    }
+
+    /// Every 20 MHz channel allowed on the band as a primary channel.
+    pub fn channels(&self, band: Band) -> Vec<u8> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.band == band)
+            .flat_map(|rule| {
+                (rule.first..=rule.last)
+                    .step_by(band.step() as usize)
+                    .filter(move |channel| !rule.psc_only || is_psc(*channel))
+            })
+            .collect()
+    }
+
+    /// The 2.4 GHz channels that don't overlap each other.
+    /// Four fit where channel 13 is allowed, three otherwise. Channel 14 is 802.11b only.
+    pub fn candidates_2g(&self) -> Vec<u8> {
+        let plan: &[u8] = match self.rule(Band::Ghz2_4, 13) {
+            Some(_) => &[1, 5, 9, 13],
+            None => &[1, 6, 11],
+        };
+
+        plan.iter()
+            .copied()
+            .filter(|channel| {
+                self.rule(Band::Ghz2_4, *channel)
+                    .map(|rule| !rule.dsss_only)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Whether every 20 MHz channel of a block is allowed, leaving out DFS ones unless `dfs`.
+    pub fn allows_block(&self, band: Band, center: u8, mhz: u16, dfs: bool) -> bool {
+        wifi::block_channels(center, mhz).iter().all(|channel| {
+            self.rule(band, *channel)
+                .map(|rule| dfs || !rule.dfs)
+                .unwrap_or_default()
+        })
+    }
+
+    /// The 5 GHz blocks of the given width that are allowed, by center channel.
+    pub fn blocks_5g(&self, mhz: u16, dfs: bool) -> Vec<u8> {
+        let centers = match mhz {
+            20 => self.channels(Band::Ghz5),
+            _ => BLOCKS_5G
+                .iter()
+                .find(|(width, _)| *width == mhz)
+                .map(|(_, centers)| centers.to_vec())
+                .unwrap_or_default(),
+        };
+
+        centers
+            .into_iter()
+            .filter(|center| self.allows_block(Band::Ghz5, *center, mhz, dfs))
+            .collect()
+    }
+
+    /// The 6 GHz blocks of the given width that are allowed and hold a preferred scanning channel
+    /// to be the primary, by center channel. Of 320 MHz blocks only the 320-1 set is used, the
+    /// 320-2 one overlaps it.
+    pub fn blocks_6g(&self, mhz: u16) -> Vec<u8> {
+        // a block of n 20 MHz channels spans 4n channel numbers, its center is half way through
+        let span = (mhz / 5) as u8;
+        let offset = (span - 4) / 2;
+
+        (1 + offset..=233 - offset)
+            .step_by(span as usize)
+            .filter(|center| wifi::block_channels(*center, mhz).into_iter().any(is_psc))
+            .filter(|center| self.allows_block(Band::Ghz6, *center, mhz, true))
+            .collect()
+    }
+
+    /// Highest EIRP allowed over a whole block, the quietest of its 20 MHz channels.
+    pub fn max_eirp(&self, band: Band, center: u8, mhz: u16) -> Option<i8> {
+        wifi::block_channels(center, mhz)
+            .iter()
+            .map(|channel| self.rule(band, *channel).map(|rule| rule.max_eirp))
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .min()
+    }
+}
+
+/// The country most networks in the scan announce in their Country element.
+/// Ties go to the first code alphabetically.
+pub fn detect_country(networks: &[Wifi]) -> Option<String> {
+    networks
+        .iter()
+        .filter_map(|wifi| wifi.country.as_ref())
+        .map(|country| country.code.to_uppercase())
+        .counts()
+        .into_iter()
+        .sorted_by(|(code_a, count_a), (code_b, count_b)| {
+            count_b.cmp(count_a).then_with(|| code_a.cmp(code_b))
+        })
+        .map(|(code, _)| code)
+        .next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_6g_keep_a_psc() {
+        assert_eq!(FCC.blocks_6g(20), (5..=229).step_by(16).collect::<Vec<_>>());
+        assert_eq!(FCC.blocks_6g(40)[..3], [3, 19, 35]);
+        assert_eq!(FCC.blocks_6g(80).len(), 14);
+        assert_eq!(FCC.blocks_6g(160), [15, 47, 79, 111, 143, 175, 207]);
+        assert_eq!(FCC.blocks_6g(320), [31, 95, 159]);
+    }
+
+    #[test]
+    fn world_rules_keep_to_lower_6g_pscs() {
+        assert_eq!(WORLD.channels(Band::Ghz6), [5, 21, 37, 53, 69, 85]);
+        assert_eq!(WORLD.blocks_6g(160), [15, 47, 79]);
+        assert_eq!(WORLD.blocks_6g(320), [31]);
+        assert_eq!(WORLD.max_eirp(Band::Ghz6, 7, 80), Some(14));
+        assert!(SRRC.blocks_6g(80).is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// What a BSS advertises in its RSN, WPA and WPS elements.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Security {
+    pub auth: AuthType,
+    pub akm_suites: Vec<Akm>,
+    pub pairwise_ciphers: Vec<Cipher>,
+    pub group_cipher: Option<Cipher>,
+    pub pmf_required: bool,
+    pub pmf_capable: bool,
+    pub wps: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AuthType {
+    Open,
+    Owe,
+    Wep,
+    Wpa,
+    Wpa2,
+    // SAE and PSK side by side, so WPA2 only clients can still join
+    Wpa3Transition,
+    Wpa3,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Akm {
+    Ieee8021x,
+    Psk,
+    FtIeee8021x,
+    FtPsk,
+    Ieee8021xSha256,
+    PskSha256,
+    Sae,
+    FtSae,
+    SuiteB,
+    SuiteB192,
+    Owe,
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Cipher {
+    Wep40,
+    Tkip,
+    Ccmp,
+    Wep104,
+    Gcmp,
+    Gcmp256,
+    Ccmp256,
+    Other(String),
+}
+
+/// The cipher and AKM suites of an RSN or WPA element, before they are combined into `Security`.
+#[derive(Debug, Default)]
+pub struct Suites {
+    pub group_cipher: Option<Cipher>,
+    pub pairwise_ciphers: Vec<Cipher>,
+    pub akm_suites: Vec<Akm>,
+    pub pmf_required: bool,
+    pub pmf_capable: bool,
+}
+
+impl Security {
+    /// Combines the elements of a BSS. RSN wins over WPA when both are present,
+    /// without either the privacy capability bit tells WEP from open.
+    pub fn new(rsn: Option<Suites>, wpa: Option<Suites>, privacy: bool, wps: bool) -> Self {
+        let (auth, suites) = match (rsn, wpa) {
+            (Some(rsn), _) => (Self::rsn_auth(&rsn.akm_suites), rsn),
+            (None, Some(wpa)) => (AuthType::Wpa, wpa),
+            (None, None) if privacy => (AuthType::Wep, Suites::default()),
+            (None, None) => (AuthType::Open, Suites::default()),
+        };
+
+        Self {
+            auth,
+            akm_suites: suites.akm_suites,
+            pairwise_ciphers: suites.pairwise_ciphers,
+            group_cipher: suites.group_cipher,
+            pmf_required: suites.pmf_required,
+            pmf_capable: suites.pmf_capable,
+            wps,
+        }
+    }
+
+    fn rsn_auth(akms: &[Akm]) -> AuthType {
+        let has = |akm: &Akm| akms.contains(akm);
+        let sae = has(&Akm::Sae) || has(&Akm::FtSae);
+        let psk = has(&Akm::Psk) || has(&Akm::FtPsk) || has(&Akm::PskSha256);
+
+        match () {
+            _ if has(&Akm::Owe) => AuthType::Owe,
+            _ if sae && psk => AuthType::Wpa3Transition,
+            _ if sae || has(&Akm::SuiteB) || has(&Akm::SuiteB192) => AuthType::Wpa3,
+            _ => AuthType::Wpa2,
+        }
+    }
+}
+
+impl Akm {
+    /// From a suite type under the IEEE OUI 00-0f-ac, which WPA's 00-50-f2 mirrors for 1 and 2.
+    pub fn from_suite(oui: [u8; 3], kind: u8) -> Self {
+        match (oui, kind) {
+            (_, 1) => Self::Ieee8021x,
+            (_, 2) => Self::Psk,
+            ([0x00, 0x0f, 0xac], 3) => Self::FtIeee8021x,
+            ([0x00, 0x0f, 0xac], 4) => Self::FtPsk,
+            ([0x00, 0x0f, 0xac], 5) => Self::Ieee8021xSha256,
+            ([0x00, 0x0f, 0xac], 6) => Self::PskSha256,
+            ([0x00, 0x0f, 0xac], 8) => Self::Sae,
+            ([0x00, 0x0f, 0xac], 9) => Self::FtSae,
+            ([0x00, 0x0f, 0xac], 11) => Self::SuiteB,
+            ([0x00, 0x0f, 0xac], 12) => Self::SuiteB192,
+            ([0x00, 0x0f, 0xac], 18) => Self::Owe,
+            _ => Self::Other(format_suite(oui, kind)),
+        }
+    }
+
+    /// From the names `iw` prints under "Authentication suites".
+    pub fn from_iw(name: &str) -> Self {
+        match name {
+            "IEEE 802.1X" => Self::Ieee8021x,
+            "PSK" => Self::Psk,
+            "FT/IEEE 802.1X" => Self::FtIeee8021x,
+            "FT/PSK" => Self::FtPsk,
+            "IEEE 802.1X/SHA-256" => Self::Ieee8021xSha256,
+            "PSK/SHA-256" => Self::PskSha256,
+            "SAE" => Self::Sae,
+            "FT/SAE" => Self::FtSae,
+            "IEEE 802.1X/SUITE-B" => Self::SuiteB,
+            "IEEE 802.1X/SUITE-B-192" => Self::SuiteB192,
+            "OWE" => Self::Owe,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// Splits an "Authentication suites" line, some suite names contain spaces themselves.
+    pub fn parse_iw_list(list: &str) -> Vec<Self> {
+        let mut out = vec![];
+        let mut words = list.split_whitespace().peekable();
+
+        while let Some(word) = words.next() {
+            let name = match (word, words.peek()) {
+                ("IEEE", Some(next)) | ("FT/IEEE", Some(next)) => {
+                    let name = format!("{} {}", word, next);
+                    words.next();
+                    name
+                }
+                _ => word.to_string(),
+            };
+
+            out.push(Self::from_iw(&name));
+        }
+
+        out
+    }
+}
+
+impl Cipher {
+    pub fn from_suite(oui: [u8; 3], kind: u8) -> Self {
+        match kind {
+            1 => Self::Wep40,
+            2 => Self::Tkip,
+            4 => Self::Ccmp,
+            5 => Self::Wep104,
+            8 if oui == [0x00, 0x0f, 0xac] => Self::Gcmp,
+            9 if oui == [0x00, 0x0f, 0xac] => Self::Gcmp256,
+            10 if oui == [0x00, 0x0f, 0xac] => Self::Ccmp256,
+            _ => Self::Other(format_suite(oui, kind)),
+        }
+    }
+
+    /// From the names `iw` prints for group and pairwise ciphers.
+    pub fn from_iw(name: &str) -> Self {
+        match name {
+            "WEP-40" => Self::Wep40,
+            "TKIP" => Self::Tkip,
+            "CCMP" => Self::Ccmp,
+            "WEP-104" => Self::Wep104,
+            "GCMP" => Self::Gcmp,
+            "GCMP-256" => Self::Gcmp256,
+            "CCMP-256" => Self::Ccmp256,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+fn format_suite(oui: [u8; 3], kind: u8) -> String {
+    format!("{:02x}-{:02x}-{:02x}:{}", oui[0], oui[1], oui[2], kind)
+}
+
+impl Display for Security {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.auth)?;
+
+        if !self.akm_suites.is_empty() {
+            let akms = self
+                .akm_suites
+                .iter()
+                .map(|akm| format!("{:?}", akm))
+                .collect::<Vec<_>>();
+            write!(f, " ({})", akms.join(", "))?;
+        }
+
+        if !self.pairwise_ciphers.is_empty() {
+            let ciphers = self
+                .pairwise_ciphers
+                .iter()
+                .map(|cipher| format!("{:?}", cipher))
+                .collect::<Vec<_>>();
+            write!(f, " {}", ciphers.join("/"))?;
+        }
+
+        if self.pmf_required {
+            f.write_str(", PMF required")?;
+        } else if self.pmf_capable {
+            f.write_str(", PMF capable")?;
+        }
+
+        if self.wps {
+            f.write_str(", WPS")?;
+        }
+
+        Ok(())
+    }
+}
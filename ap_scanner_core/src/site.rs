@@ -0,0 +1,302 @@
+// Site surveys, many measures taken around the same local merged into a single plan.
+//
+// Every measure is a point of the site, a room or a floor. Networks are matched across points by
+// BSSID and a plan is scored by the worst interference any of the points would see, so a quiet
+// room can't hide a noisy one.
+use super::fleet::Fleet;
+use super::measure::Measure;
+use super::planner::ChannelPlanner;
+use super::reading::Reading;
+use super::wifi::Wifi;
+use anyhow::{anyhow, Context, Result};
+use itertools::Itertools;
+use std::{
+    cmp::Ordering,
+    collections::{btree_map::Entry, BTreeMap},
+    path::Path,
+};
+
+/// A network as seen from all the points of a site.
+#[derive(Debug)]
+pub struct Observation {
+    // as reported where it was heard the loudest
+    pub wifi: Wifi,
+    // signal in dBm at each point, `None` where it wasn't heard
+    pub signals: Vec<Option<f32>>,
+}
+
+impl Observation {
+    fn heard(&self) -> impl Iterator<Item = f32> + '_ {
+        self.signals.iter().flatten().copied()
+    }
+
+    /// Number of points the network was heard at.
+    pub fn seen(&self) -> usize {
+        self.heard().count()
+    }
+
+    pub fn max(&self) -> f32 {
+        self.heard().fold(f32::MIN, f32::max)
+    }
+
+    /// Mean signal over the points the network was heard at.
+    pub fn mean(&self) -> f32 {
+        self.heard().sum::<f32>() / self.seen().max(1) as f32
+    }
+
+    /// Signal `percent` of the points it was heard at are at or below, by nearest rank.
+    pub fn percentile(&self, percent: f32) -> f32 {
+        let sorted = self
+            .heard()
+            .sorted_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+            .collect_vec();
+        let rank = (percent / 100.0 * sorted.len() as f32).ceil() as usize;
+
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+}
+
+#[derive(Debug)]
+pub struct Site {
+    pub local: String,
+    // number of measures merged
+    pub points: usize,
+    // sorted by BSSID
+    pub observations: Vec<Observation>,
+    // the country the networks of most measures announce
+    country: Option<String>,
+    // files left out while loading and why, for the caller to report
+    pub warnings: Vec<String>,
+}
+
+impl Site {
+    /// The country the networks of most measures announce.
+    pub fn country(&self) -> Option<&str> {
+        self.country.as_deref()
+    }
+
+    /// Merges measures of the same local, in the order they were taken.
+    pub fn new(mut measures: Vec<Measure>) -> Result<Self> {
+        let local = measures
+            .first()
+            .map(|measure| measure.local.clone())
+            .context("No measures to merge.")?;
+
+        if let Some(other) = measures.iter().find(|measure| measure.local != local) {
+            return Err(anyhow!(
+                "Measures of different places, {:?} and {:?}.",
+                local,
+                other.local
+            ));
+        }
+
+        measures.sort_by_key(|measure| measure.timestamp);
+
+        let points = measures.len();
+        let country = measures
+            .iter()
+            .filter_map(Measure::country)
+            .counts()
+            .into_iter()
+            .sorted_by(|(code_a, count_a), (code_b, count_b)| {
+                count_b.cmp(count_a).then_with(|| code_a.cmp(code_b))
+            })
+            .map(|(code, _)| code)
+            .next();
+
+        let mut observations: BTreeMap<String, Observation> = BTreeMap::new();
+
+        for (point, measure) in measures.into_iter().enumerate() {
+            for wifi in measure.networks {
+                let signal = wifi.signal;
+
+                match observations.entry(wifi.mac.to_lowercase()) {
+                    Entry::Vacant(entry) => {
+                        let mut signals = vec![None; points];
+                        signals[point] = Some(signal);
+
+                        entry.insert(Observation { wifi, signals });
+                    }
+                    Entry::Occupied(mut entry) => {
+                        let observation = entry.get_mut();
+
+                        // the same BSS may show up twice in one measure, keep the loudest
+                        if observation.signals[point].map_or(true, |other| other < signal) {
+                            observation.signals[point] = Some(signal);
+                        }
+
+                        if signal > observation.wifi.signal {
+                            observation.wifi = wifi;
+                        }
+                    }
+                }
+            }
+        }
+
+        let observations = observations.into_values().collect();
+
+        Ok(Self {
+            local,
+            points,
+            observations,
+            country,
+            warnings: vec![],
+        })
+    }
+
+    /// Loads every measure saved in `dir`, or the networks of every reading, and merges those of
+    /// `local`. Without a local, all the measures have to be of the same place.
+    pub fn load(dir: impl AsRef<Path>, local: Option<&str>) -> Result<Self> {
+        let dir = dir.as_ref();
+
+        let mut warnings = vec![];
+
+        let measures = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to open directory {:?}", dir))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+            .sorted()
+            .filter_map(|path| {
+                // other json files may share the directory
+                Measure::load(path.to_string_lossy().into_owned())
+                    .map_err(|err| warnings.push(format!("Skipping {:?}: {:#}", path, err)))
+                    .ok()
+            })
+            .filter(|measure| local.map_or(true, |local| measure.local == local))
+            .collect_vec();
+
+        if measures.is_empty() {
+            return Err(anyhow!(
+                "No measures of {:?} in {:?}.",
+                local.unwrap_or("any place"),
+                dir
+            ));
+        }
+
+        let mut site = Self::new(measures).context("Pick one place with --place.")?;
+        site.warnings = warnings;
+
+        Ok(site)
+    }
+
+    /// Plans every network of the site at once, see `Reading::plan`.
+    /// Survey data isn't merged, a channel being busy at one point says little about another.
+    pub fn plan(
+        &self,
+        country: Option<String>,
+        fleet: &Fleet,
+        planner: &dyn ChannelPlanner,
+        seed: u64,
+    ) -> Result<Reading> {
+        let country = country
+            .map(|code| code.trim().to_uppercase())
+            .or_else(|| self.country.clone());
+
+        let networks = self
+            .observations
+            .iter()
+            .map(|observation| (observation.wifi.clone(), observation.signals.clone()))
+            .collect();
+
+        Reading::plan(
+            self.local.clone(),
+            networks,
+            country,
+            BTreeMap::new(),
+            fleet,
+            planner,
+            seed,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wifi::test_wifi;
+
+    fn measure(local: &str, timestamp: u128, networks: Vec<Wifi>) -> Measure {
+        let mut measure = Measure::new(local.to_string(), networks, vec![], vec![]).unwrap();
+        measure.timestamp = timestamp;
+        measure
+    }
+
+    #[test]
+    fn merges_by_lowercase_bssid() {
+        let site = Site::new(vec![
+            measure(
+                "Office",
+                2,
+                vec![test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -50.0)],
+            ),
+            measure(
+                "Office",
+                1,
+                vec![
+                    test_wifi("Office", "3C:37:86:1A:2B:3D", 6, -60.0),
+                    test_wifi("Cafe", "02:00:00:00:00:01", 11, -80.0),
+                ],
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(site.points, 2);
+        assert_eq!(site.observations.len(), 2);
+
+        // in the order the measures were taken, as reported where it was the loudest
+        let office = &site.observations[1];
+        assert_eq!(office.signals, vec![Some(-60.0), Some(-50.0)]);
+        assert_eq!(office.wifi.mac, "3c:37:86:1a:2b:3d");
+        assert_eq!(site.observations[0].signals, vec![Some(-80.0), None]);
+    }
+
+    #[test]
+    fn keeps_the_loudest_duplicate() {
+        let site = Site::new(vec![measure(
+            "Office",
+            1,
+            vec![
+                test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -70.0),
+                test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -55.0),
+                test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -65.0),
+            ],
+        )])
+        .unwrap();
+
+        assert_eq!(site.observations.len(), 1);
+        assert_eq!(site.observations[0].signals, vec![Some(-55.0)]);
+        assert_eq!(site.observations[0].wifi.signal, -55.0);
+    }
+
+    #[test]
+    fn rejects_mixed_places() {
+        let err = Site::new(vec![
+            measure("Office", 1, vec![]),
+            measure("Warehouse", 2, vec![]),
+        ])
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Measures of different places, \"Office\" and \"Warehouse\"."
+        );
+        assert!(Site::new(vec![]).is_err());
+    }
+
+    #[test]
+    fn percentile_by_nearest_rank() {
+        let observation = Observation {
+            wifi: test_wifi("Office", "3c:37:86:1a:2b:3d", 6, -50.0),
+            signals: vec![Some(-60.0), None, Some(-80.0), Some(-50.0), Some(-70.0)],
+        };
+
+        assert_eq!(observation.percentile(0.0), -80.0);
+        assert_eq!(observation.percentile(10.0), -80.0);
+        assert_eq!(observation.percentile(25.0), -80.0);
+        assert_eq!(observation.percentile(50.0), -70.0);
+        assert_eq!(observation.percentile(51.0), -60.0);
+        assert_eq!(observation.percentile(100.0), -50.0);
+        assert_eq!(observation.seen(), 4);
+        assert_eq!(observation.mean(), -65.0);
+    }
+}
@@ -0,0 +1,49 @@
+// Channel survey data, how busy each channel was while the radio listened on it.
+//
+// Drivers report different subsets of the fields, all but the frequency are optional.
+use serde::{Deserialize, Serialize};
+
+/// How busy a channel was while the radio listened on it.
+/// Times are in milliseconds, accumulated by the driver since it started counting.
+/// It's shown alongside the suggestions but never weighed by the planners, the busy time of a
+/// short dwell on a channel is too noisy to move a plan.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ChannelSurvey {
+    pub frequency: u16,
+    // the channel the interface is currently tuned to
+    #[serde(default)]
+    pub in_use: bool,
+    // noise floor in dBm
+    pub noise: Option<i8>,
+    pub active_time: Option<u64>,
+    pub busy_time: Option<u64>,
+    pub receive_time: Option<u64>,
+    pub transmit_time: Option<u64>,
+}
+
+impl ChannelSurvey {
+    /// Share of the active time the medium was sensed busy, between 0 and 1.
+    pub fn busy_ratio(&self) -> Option<f32> {
+        match (self.active_time, self.busy_time) {
+            (Some(active), Some(busy)) if active > 0 => Some(busy as f32 / active as f32),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busy_ratio_needs_active_time() {
+        let survey = ChannelSurvey {
+            frequency: 2412,
+            active_time: Some(0),
+            busy_time: Some(10),
+            ..Default::default()
+        };
+
+        assert_eq!(survey.busy_ratio(), None);
+    }
+}
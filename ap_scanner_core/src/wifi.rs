@@ -0,0 +1,216 @@
+use super::{
+    bss::{BssLoad, Capability, Country},
+    regulatory::{self, Band},
+    security::Security,
+};
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+// Stored as the bare frequency, the band is worked out again when loading.
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "u16", into = "u16")]
+pub enum Frequency {
+    Freq2400MHz(u16),
+    Freq5000MHz(u16),
+    Freq6000MHz(u16),
+}
+
+impl FromStr for Frequency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // newer versions of iw print fractional frequencies, e.g. "2412.0"
+        Ok((s.trim().parse::<f32>()?.round() as u16).into())
+    }
+}
+
+impl From<u16> for Frequency {
+    fn from(val: u16) -> Self {
+        if val < 2500 {
+            Self::Freq2400MHz(val)
+        } else if (5925..=7125).contains(&val) {
+            Self::Freq6000MHz(val)
+        } else {
+            // if the frequency is higher than channel 13
+            Self::Freq5000MHz(val)
+        }
+    }
+}
+
+impl From<Frequency> for u16 {
+    fn from(freq: Frequency) -> Self {
+        freq.mhz()
+    }
+}
+
+impl Frequency {
+    pub fn mhz(&self) -> u16 {
+        match self {
+            Self::Freq2400MHz(val) | Self::Freq5000MHz(val) | Self::Freq6000MHz(val) => *val,
+        }
+    }
+
+    /// Channel number of this center frequency, used when an AP doesn't announce its channel.
+    pub fn channel(&self) -> Option<u8> {
+        let channel = match self.mhz() {
+            2484 => 14,
+            val @ 2412..=2472 => (val - 2407) / 5,
+            // 4.9 GHz public safety band, numbered from 4 GHz
+            val @ 4910..=4980 => (val - 4000) / 5,
+            val @ 5000..=5895 => (val - 5000) / 5,
+            // channel 2 is the odd one out, below channel 1
+            5935 => 2,
+            val @ 5955..=7115 => (val - 5950) / 5,
+            _ => return None,
+        };
+
+        Some(channel as u8)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Width {
+    MHz20,
+    MHz40,
+    MHz80,
+    MHz160,
+    MHz320,
+}
+
+impl Width {
+    pub fn mhz(&self) -> u16 {
+        match self {
+            Self::MHz20 => 20,
+            Self::MHz40 => 40,
+            Self::MHz80 => 80,
+            Self::MHz160 => 160,
+            Self::MHz320 => 320,
+        }
+    }
+}
+
+impl FromStr for Width {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split(' ').next().and_then(|val| val.parse::<u16>().ok()) {
+            Some(20) => Ok(Self::MHz20),
+            Some(40) => Ok(Self::MHz40),
+            Some(80) => Ok(Self::MHz80),
+            Some(160) => Ok(Self::MHz160),
+            Some(320) => Ok(Self::MHz320),
+            _ => Err(anyhow!("Couldn't parse channel width.")),
+        }
+    }
+}
+
+// Alternatively iwlist iface scanning could yield quality levels
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Wifi {
+    pub ssid: String,
+    pub mac: String,
+    pub channel: u8,
+    pub signal: f32,
+    pub frequency: Frequency,
+    pub width: Width,
+    // center channel of the operating block, readings taken before it was recorded lack it
+    #[serde(default)]
+    pub center_channel: Option<u8>,
+    // center channel of the second segment of an 80+80 MHz BSS
+    #[serde(default)]
+    pub center_channel_2: Option<u8>,
+    // unknown for readings taken before it was recorded
+    #[serde(default)]
+    pub security: Option<Security>,
+    // the fields below are only there when the AP advertises them
+    #[serde(default)]
+    pub bss_load: Option<BssLoad>,
+    #[serde(default)]
+    pub country: Option<Country>,
+    // in TUs of 1024 us
+    #[serde(default)]
+    pub beacon_interval: Option<u16>,
+    #[serde(default)]
+    pub capability: Option<Capability>,
+}
+
+impl Wifi {
+    pub fn is_2_4g(&self) -> bool {
+        matches!(self.frequency, Frequency::Freq2400MHz(_))
+    }
+
+    pub fn is_5g(&self) -> bool {
+        matches!(self.frequency, Frequency::Freq5000MHz(_))
+    }
+
+    pub fn is_6g(&self) -> bool {
+        matches!(self.frequency, Frequency::Freq6000MHz(_))
+    }
+
+    pub fn band(&self) -> Band {
+        match self.frequency {
+            Frequency::Freq2400MHz(_) => Band::Ghz2_4,
+            Frequency::Freq5000MHz(_) => Band::Ghz5,
+            Frequency::Freq6000MHz(_) => Band::Ghz6,
+        }
+    }
+
+    /// Whether the primary channel is one of the 6 GHz preferred scanning channels.
+    pub fn is_psc(&self) -> bool {
+        self.is_6g() && regulatory::is_psc(self.channel)
+    }
+
+    /// The 20 MHz channels this BSS transmits on.
+    pub fn sub_channels(&self) -> Vec<u8> {
+        let center = self.center_channel.unwrap_or(self.channel);
+
+        match self.center_channel_2 {
+            Some(center_2) => [center, center_2]
+                .iter()
+                .flat_map(|center| block_channels(*center, 80))
+                .collect(),
+            None => block_channels(center, self.width.mhz()),
+        }
+    }
+}
+
+/// The 20 MHz channels of a block, 20 MHz channels being 4 channel numbers apart.
+pub fn block_channels(center: u8, mhz: u16) -> Vec<u8> {
+    let count = (mhz / 20) as i16;
+    let first = center as i16 - 2 * (count - 1);
+
+    (0..count)
+        .map(|i| first + 4 * i)
+        .filter(|channel| (1..=u8::MAX as i16).contains(channel))
+        .map(|channel| channel as u8)
+        .collect()
+}
+
+/// A 20 MHz network heard with `signal`, for the tests of the modules built on it.
+#[cfg(test)]
+pub(crate) fn test_wifi(ssid: &str, mac: &str, channel: u8, signal: f32) -> Wifi {
+    let mhz = match channel {
+        14 => 2484,
+        1..=13 => 2407 + 5 * channel as u16,
+        _ => 5000 + 5 * channel as u16,
+    };
+
+    Wifi {
+        ssid: ssid.to_string(),
+        mac: mac.to_string(),
+        channel,
+        signal,
+        frequency: mhz.into(),
+        width: Width::MHz20,
+        center_channel: None,
+        center_channel_2: None,
+        security: None,
+        bss_load: None,
+        country: None,
+        beacon_interval: None,
+        capability: None,
+    }
+}
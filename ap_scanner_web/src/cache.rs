@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::readings::{Reading, Suggestion};
+use crate::readings::{Power, Rationale, Reading, Security, Suggestion};
 use std::{collections::HashMap, io::Write, sync::Arc, sync::Mutex};
 
 #[derive(Serialize, Deserialize)]
@@ -8,6 +8,12 @@ struct _InnerValues {
     pub ssid: String,
     pub file: String,
     pub suggestion: Suggestion,
+    #[serde(default)]
+    pub security: Option<Security>,
+    #[serde(default)]
+    pub rationale: Option<Rationale>,
+    #[serde(default)]
+    pub power: Option<Power>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -39,23 +45,35 @@ impl Cache {
             .wifi_2_4_ghz
             .iter()
             .chain(reading.wifi_5_ghz.iter())
-            .map(|(_, pair)| {
+            .chain(reading.wifi_6_ghz.iter())
+            .flat_map(|(_, pair)| {
                 pair.iter()
-                    .map(|(w, s)| (w.ssid.clone(), w.mac.clone(), s.clone()))
+                    .map(|(w, s)| {
+                        (
+                            w.ssid.clone(),
+                            w.mac.clone(),
+                            *s,
+                            w.security.clone(),
+                            reading.rationale.get(&w.mac).cloned(),
+                            reading.power.get(&w.mac).cloned(),
+                        )
+                    })
                     .collect::<Vec<_>>()
             })
-            .flatten()
             .collect::<Vec<_>>();
 
         let mut cache = CACHE.lock().unwrap();
 
-        for (ssid, mac, suggestion) in data {
+        for (ssid, mac, suggestion, security, rationale, power) in data {
             cache.0.insert(
                 mac,
                 _InnerValues {
                     ssid,
                     file: file.to_string(),
-                    suggestion: suggestion.clone(),
+                    suggestion,
+                    security,
+                    rationale,
+                    power,
                 },
             );
         }
@@ -65,17 +83,46 @@ impl Cache {
     pub fn get_suggestion(ssid: &str, mac: &str) -> Option<Suggestion> {
         let cache = CACHE.lock().unwrap();
 
+        cache.0.get(mac).and_then(|val| {
+            if val.ssid.eq(ssid) {
+                Some(val.suggestion)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Given a SSID and MAC, returns the security the device advertised in its latest reading.
+    pub fn get_security(ssid: &str, mac: &str) -> Option<Security> {
+        let cache = CACHE.lock().unwrap();
+
         cache
             .0
             .get(mac)
-            .map(|val| {
-                if val.ssid.eq(ssid) {
-                    Some(val.suggestion.clone())
-                } else {
-                    None
-                }
-            })
-            .flatten()
+            .filter(|val| val.ssid.eq(ssid))
+            .and_then(|val| val.security.clone())
+    }
+
+    /// Given a SSID and MAC, returns why the latest suggestion was made.
+    pub fn get_rationale(ssid: &str, mac: &str) -> Option<Rationale> {
+        let cache = CACHE.lock().unwrap();
+
+        cache
+            .0
+            .get(mac)
+            .filter(|val| val.ssid.eq(ssid))
+            .and_then(|val| val.rationale.clone())
+    }
+
+    /// Given a SSID and MAC, returns the transmit power advice of the latest reading.
+    pub fn get_power(ssid: &str, mac: &str) -> Option<Power> {
+        let cache = CACHE.lock().unwrap();
+
+        cache
+            .0
+            .get(mac)
+            .filter(|val| val.ssid.eq(ssid))
+            .and_then(|val| val.power.clone())
     }
 
     pub fn from_file(path: &str) {
@@ -91,7 +138,7 @@ impl Cache {
         let cache = &CACHE.lock().unwrap().0;
         let val = serde_json::to_string(cache).unwrap();
         let mut file = std::fs::File::create(path)?;
-        file.write_all(&val.as_bytes())?;
+        file.write_all(val.as_bytes())?;
         Ok(())
     }
 }
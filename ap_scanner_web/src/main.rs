@@ -8,6 +8,9 @@ mod routes;
 use routes::*;
 mod cache;
 use cache::Cache;
+use readings::Strategy;
+use rocket::fairing::AdHoc;
+use serde::Deserialize;
 
 lazy_static::lazy_static! {
     // static cache: HashMap<(SSID,MAC), Filename> = HashMap::new();
@@ -17,6 +20,14 @@ lazy_static::lazy_static! {
     static ref SCAN_PATH: String = "upload".into();
 }
 
+/// Settings read from `Rocket.toml` or `ROCKET_` environment variables.
+#[derive(Deserialize)]
+pub struct Config {
+    /// how site channels are handed out, `greedy`, `colouring` or `annealing`
+    #[serde(default)]
+    planner: Strategy,
+}
+
 #[launch]
 fn rocket() -> _ {
     // create the directory
@@ -30,7 +41,7 @@ fn rocket() -> _ {
         Cache::to_file("upload/cache").unwrap_or(());
     });
 
-    rocket::build().mount(
+    rocket::build().attach(AdHoc::config::<Config>()).mount(
         "/",
         routes![
             suggestion,
@@ -41,6 +52,14 @@ fn rocket() -> _ {
             index,
             file_suggestion_raw,
             file_suggestion,
+            survey,
+            widths,
+            matrix_csv,
+            matrix_json,
+            diff,
+            diff_raw,
+            site_plan,
+            site_plan_raw,
         ],
     )
 }
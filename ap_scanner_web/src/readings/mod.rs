@@ -1,7 +1,16 @@
-mod reading;
 mod reading_id;
-mod wifi;
 
-pub use reading::{Reading, Suggestion, Suggestions5G};
+// the model and the planning, shared with the scanner
+pub use ap_scanner_core::{
+    diff::{Diff, THRESHOLD_DB},
+    fleet::Fleet,
+    matrix::InterferenceMatrix,
+    measure::Measure,
+    planner::Strategy,
+    reading::{Power, Rationale, Reading, Suggestion, WidthAdvice},
+    regulatory::{Band, Domain},
+    security::Security,
+    site::Site,
+    wifi::Wifi,
+};
 pub use reading_id::ReadingID;
-pub use wifi::{Frequency, Width, Wifi};
@@ -1,11 +1,14 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeMap};
 
-use crate::{readings::Reading, Cache, HOST, SCAN_PATH};
-use rocket::tokio::fs::File;
+use crate::{readings::Reading, Cache, Config, HOST, SCAN_PATH};
+use rocket::{request::FromParam, tokio::fs::File, State};
 use serde::Serialize;
 use walkdir::WalkDir;
 
-use crate::readings::ReadingID;
+use crate::readings::{
+    Band, Diff, Domain, Fleet, InterferenceMatrix, Measure, Power, Rationale, ReadingID, Security,
+    Site, Suggestion, WidthAdvice, Wifi, THRESHOLD_DB,
+};
 
 /// Fetches a list of JSON files inside the scans folder.
 fn get_scans() -> Vec<String> {
@@ -66,12 +69,24 @@ pub async fn suggestion(ssid: &str, mac: &str) -> Option<String> {
     output.push_str(format!("SSID: {}\n", ssid).as_str());
     output.push_str(format!("MAC: {}\n", mac).as_str());
 
+    if let Some(security) = Cache::get_security(ssid, mac) {
+        output.push_str(format!("Security: {}\n", security).as_str());
+    }
+
     if let Some(suggestion) = Cache::get_suggestion(ssid, mac) {
         output.push_str(format!("Suggestion: {:?}\n", suggestion).as_str());
     } else {
         output.push_str("No suggestion available.\n");
     }
 
+    if let Some(rationale) = Cache::get_rationale(ssid, mac) {
+        output.push_str(format!("{}\n", rationale).as_str());
+    }
+
+    if let Some(power) = Cache::get_power(ssid, mac) {
+        output.push_str(format!("{}\n", power).as_str());
+    }
+
     Some(output)
 }
 
@@ -80,25 +95,29 @@ struct RawScan<'a> {
     ssid: Cow<'a, str>,
     mac: Cow<'a, str>,
     suggestion: String,
+    security: Option<Security>,
+    rationale: Option<Rationale>,
+    power: Option<Power>,
 }
 
 /// Generates a JSON with the necessary data and serves it.
 /// This differs from `suggestion` in the fact that the data isn't meant to be human readable.
 #[get("/<ssid>/<mac>/raw")]
 pub async fn suggestion_raw(ssid: &str, mac: &str) -> Option<String> {
-    let suggestion = Cache::get_suggestion(ssid, mac).and_then(|cache| {
-        let fodasse =
-            serde_json::to_string(&cache).unwrap_or("No suggestion available.".to_string());
-        Some(fodasse)
+    let suggestion = Cache::get_suggestion(ssid, mac).map(|cache| {
+        serde_json::to_string(&cache).unwrap_or_else(|_| "No suggestion available.".to_string())
     })?;
 
     let output = RawScan {
         ssid: Cow::Borrowed(ssid),
         mac: Cow::Borrowed(mac),
         suggestion,
+        security: Cache::get_security(ssid, mac),
+        rationale: Cache::get_rationale(ssid, mac),
+        power: Cache::get_power(ssid, mac),
     };
 
-    Some(serde_json::to_string(&output).unwrap_or("No suggestion available.".to_string()))
+    Some(serde_json::to_string(&output).unwrap_or_else(|_| "No suggestion available.".to_string()))
 }
 
 /// Retrieves a suggestion for a specific ssid/mac device in a file specified by the client in a json format.
@@ -112,16 +131,20 @@ pub async fn file_suggestion(id: ReadingID<'_>, ssid: &str, mac: &str) -> Option
         .wifi_2_4_ghz
         .iter()
         .chain(reading.wifi_5_ghz.iter())
+        .chain(reading.wifi_6_ghz.iter())
         .filter_map(|(_, pair)| {
             pair.iter()
                 .filter_map(|(w, s)| {
                     if w.ssid.eq(ssid) && w.mac.eq(mac) {
                         let suggestion = serde_json::to_string(s)
-                            .unwrap_or("Couldn't parse the suggestion.".into());
+                            .unwrap_or_else(|_| "Couldn't parse the suggestion.".into());
                         Some(RawScan {
                             ssid: Cow::Borrowed(ssid),
                             mac: Cow::Borrowed(mac),
                             suggestion,
+                            security: w.security.clone(),
+                            rationale: reading.rationale.get(mac).cloned(),
+                            power: reading.power.get(mac).cloned(),
                         })
                     } else {
                         None
@@ -131,16 +154,35 @@ pub async fn file_suggestion(id: ReadingID<'_>, ssid: &str, mac: &str) -> Option
         })
         .next()?;
 
+    let security = kv_match
+        .security
+        .map(|security| security.to_string())
+        .unwrap_or_else(|| "Unknown".into());
+
+    let rationale = kv_match
+        .rationale
+        .map(|rationale| format!("\n{}", rationale))
+        .unwrap_or_default();
+
+    let power = kv_match
+        .power
+        .map(|power| format!("\n{}", power))
+        .unwrap_or_default();
+
     Some(format!(
-        "AP Scanner Suggestion\nSSID: {ssid}\nMAC: {mac}\nSuggestion: {suggestion}",
+        "AP Scanner Suggestion\nSSID: {ssid}\nMAC: {mac}\nSecurity: {security}\nSuggestion: {suggestion}{rationale}{power}",
         ssid = kv_match.ssid,
         mac = kv_match.mac,
+        security = security,
         suggestion = kv_match.suggestion,
+        rationale = rationale,
+        power = power,
     ))
 }
 
 /// Retrieves a suggestion for a specific ssid/mac device in a file specified by the client in a json format.
-#[get("/<id>/<ssid>/<mac>/raw")]
+/// Ranked after `site_plan_raw`, which would otherwise be shadowed by a reading id of `site`.
+#[get("/<id>/<ssid>/<mac>/raw", rank = 1)]
 pub async fn file_suggestion_raw(id: ReadingID<'_>, ssid: &str, mac: &str) -> Option<String> {
     let file = std::fs::File::open(id.path()).ok()?;
     let reading: Reading = serde_json::from_reader(&file).ok()?;
@@ -149,16 +191,20 @@ pub async fn file_suggestion_raw(id: ReadingID<'_>, ssid: &str, mac: &str) -> Op
         .wifi_2_4_ghz
         .iter()
         .chain(reading.wifi_5_ghz.iter())
+        .chain(reading.wifi_6_ghz.iter())
         .filter_map(|(_, pair)| {
             pair.iter()
                 .filter_map(|(w, s)| {
                     if w.ssid.eq(ssid) && w.mac.eq(mac) {
                         let suggestion = serde_json::to_string(s)
-                            .unwrap_or("Couldn't parse the suggestion.".into());
+                            .unwrap_or_else(|_| "Couldn't parse the suggestion.".into());
                         Some(RawScan {
                             ssid: Cow::Borrowed(ssid),
                             mac: Cow::Borrowed(mac),
                             suggestion,
+                            security: w.security.clone(),
+                            rationale: reading.rationale.get(mac).cloned(),
+                            power: reading.power.get(mac).cloned(),
                         })
                     } else {
                         None
@@ -171,6 +217,345 @@ pub async fn file_suggestion_raw(id: ReadingID<'_>, ssid: &str, mac: &str) -> Op
     serde_json::to_string(&output).ok()
 }
 
+/// Lists the survey data of a reading, the share of time each channel was busy and its noise floor.
+#[get("/<id>/survey")]
+pub async fn survey(id: ReadingID<'_>) -> Option<String> {
+    let file = std::fs::File::open(id.path()).ok()?;
+    let reading: Reading = serde_json::from_reader(&file).ok()?;
+
+    let mut output = String::new();
+
+    output.push_str("AP Scanner Survey\n");
+
+    if reading.survey.is_empty() {
+        output.push_str("No survey data available.\n");
+        return Some(output);
+    }
+
+    let mut frequencies = reading.survey.keys().collect::<Vec<_>>();
+    frequencies.sort();
+
+    for frequency in frequencies {
+        let survey = &reading.survey[frequency];
+
+        let busy = survey
+            .busy_ratio()
+            .map(|ratio| format!("{:.0}%", ratio * 100.0))
+            .unwrap_or_else(|| "unknown".into());
+        let noise = survey
+            .noise
+            .map(|noise| format!("{} dBm", noise))
+            .unwrap_or_else(|| "unknown".into());
+
+        output.push_str(&format!(
+            "{frequency} MHz{in_use}: busy {busy}, noise {noise}\n",
+            frequency = frequency,
+            in_use = if survey.in_use { " (in use)" } else { "" },
+            busy = busy,
+            noise = noise,
+        ));
+    }
+
+    Some(output)
+}
+
+/// The width advice of each band of a reading, `None` for the bands without any.
+fn widths_of(reading: &Reading) -> [(&'static str, Option<&WidthAdvice>); 3] {
+    [
+        ("2.4GHz", reading.width_2_4_ghz.as_ref()),
+        ("5GHz", reading.width_5_ghz.as_ref()),
+        ("6GHz", reading.width_6_ghz.as_ref()),
+    ]
+}
+
+/// Appends the width advice of each band, if any of the bands has one.
+fn push_widths(output: &mut String, reading: &Reading) {
+    for (band, advice) in widths_of(reading) {
+        if let Some(advice) = advice {
+            output.push_str(&format!("Recommended width for {} networks\n", band));
+
+            for line in advice.to_string().lines() {
+                output.push_str(&format!("\t{}\n", line));
+            }
+        }
+    }
+}
+
+/// Recommends the width the networks of each band of a reading should run at, and why.
+#[get("/<id>/widths")]
+pub async fn widths(id: ReadingID<'_>) -> Option<String> {
+    let file = std::fs::File::open(id.path()).ok()?;
+    let reading: Reading = serde_json::from_reader(&file).ok()?;
+
+    let mut output = String::new();
+
+    output.push_str("AP Scanner Widths\n");
+
+    if widths_of(&reading)
+        .iter()
+        .all(|(_, advice)| advice.is_none())
+    {
+        output.push_str("No width advice available.\n");
+    }
+
+    push_widths(&mut output, &reading);
+
+    Some(output)
+}
+
+/// Exports the interference every network of a reading puts on every other, one line per pair.
+#[get("/<id>/matrix.csv")]
+pub async fn matrix_csv(id: ReadingID<'_>) -> Option<String> {
+    let file = std::fs::File::open(id.path()).ok()?;
+    let reading: Reading = serde_json::from_reader(&file).ok()?;
+
+    Some(InterferenceMatrix::new(&reading).to_csv())
+}
+
+/// Same as `matrix_csv` in a json format.
+#[get("/<id>/matrix.json")]
+pub async fn matrix_json(id: ReadingID<'_>) -> Option<String> {
+    let file = std::fs::File::open(id.path()).ok()?;
+    let reading: Reading = serde_json::from_reader(&file).ok()?;
+
+    serde_json::to_string(&InterferenceMatrix::new(&reading)).ok()
+}
+
+/// Every network of an uploaded reading.
+fn get_networks(id: ReadingID<'_>) -> Option<Vec<Wifi>> {
+    let file = std::fs::File::open(id.path()).ok()?;
+    let reading: Reading = serde_json::from_reader(&file).ok()?;
+
+    Some(
+        reading
+            .wifi_2_4_ghz
+            .into_values()
+            .chain(reading.wifi_5_ghz.into_values())
+            .chain(reading.wifi_6_ghz.into_values())
+            .flatten()
+            .map(|(wifi, _)| wifi)
+            .collect(),
+    )
+}
+
+/// Lists the networks that appeared, vanished or changed between two readings, `before` taken
+/// first. Signal changes under `threshold` dB are left out.
+#[get("/diff/<before>/<after>?<threshold>")]
+pub async fn diff(
+    before: ReadingID<'_>,
+    after: ReadingID<'_>,
+    threshold: Option<f32>,
+) -> Option<String> {
+    let diff = Diff::new(
+        &get_networks(before)?,
+        &get_networks(after)?,
+        threshold.unwrap_or(THRESHOLD_DB),
+    );
+
+    Some(format!("AP Scanner Diff\n{}\n", diff))
+}
+
+/// Same as `diff` in a json format.
+#[get("/diff/<before>/<after>/raw?<threshold>")]
+pub async fn diff_raw(
+    before: ReadingID<'_>,
+    after: ReadingID<'_>,
+    threshold: Option<f32>,
+) -> Option<String> {
+    let diff = Diff::new(
+        &get_networks(before)?,
+        &get_networks(after)?,
+        threshold.unwrap_or(THRESHOLD_DB),
+    );
+
+    serde_json::to_string(&diff).ok()
+}
+
+/// Merges every uploaded reading taken at `local` into a site, and plans it as configured with
+/// the seed of the first reading. Uploads carry no list of the networks that are ours, every
+/// network is planned.
+fn get_site(local: &str, config: &Config) -> Option<(Site, Reading)> {
+    let mut readings = get_scans()
+        .iter()
+        .filter_map(|scan| {
+            let file = std::fs::File::open(ReadingID::from_param(scan).ok()?.path()).ok()?;
+            serde_json::from_reader::<_, Reading>(file).ok()
+        })
+        .filter(|reading| reading.local == local)
+        .collect::<Vec<_>>();
+
+    readings.sort_by_key(|reading| reading.timestamp);
+    let seed = readings.first()?.seed;
+
+    let site = Site::new(readings.into_iter().map(Measure::from).collect()).ok()?;
+    let plan = site
+        .plan(
+            None,
+            &Fleet::default(),
+            config.planner.planner().as_ref(),
+            seed,
+        )
+        .ok()?;
+
+    Some((site, plan))
+}
+
+/// The suggestion of every network of a reading, by BSSID.
+fn suggestions(reading: &Reading) -> BTreeMap<&str, Suggestion> {
+    reading
+        .wifi_2_4_ghz
+        .values()
+        .chain(reading.wifi_5_ghz.values())
+        .chain(reading.wifi_6_ghz.values())
+        .flatten()
+        .map(|(wifi, suggestion)| (wifi.mac.as_str(), *suggestion))
+        .collect()
+}
+
+/// Plans all the readings uploaded for the same local at once, so the worst spot of the site
+/// decides. Lists how each network was heard around the site along with its suggestion.
+#[get("/site/<local>/plan")]
+pub async fn site_plan(local: &str, config: &State<Config>) -> Option<String> {
+    let (site, plan) = get_site(local, config)?;
+    let domain = Domain::resolve(plan.country.as_deref());
+    let suggestions = suggestions(&plan);
+
+    let mut output = String::new();
+
+    output.push_str("AP Scanner Site Plan\n");
+    output.push_str(&format!(
+        "Local: {local}\nReadings: {points}\nRegulatory domain: {domain} ({country})\nPlanner: {planner}\nSeed: {seed}\n",
+        local = site.local,
+        points = site.points,
+        domain = domain.name,
+        country = plan.country.as_deref().unwrap_or("unknown country"),
+        planner = config.planner,
+        seed = plan.seed,
+    ));
+
+    if !plan.interference_2_4_ghz.is_empty() {
+        output.push_str("Interference per 2.4GHz channel at the worst point\n");
+
+        for channel in domain.channels(Band::Ghz2_4) {
+            match plan.interference_2_4_ghz.get(&channel) {
+                Some(dbm) => output.push_str(&format!("\t{}: {:.1} dBm\n", channel, dbm)),
+                None => output.push_str(&format!("\t{}: none\n", channel)),
+            }
+        }
+    }
+
+    push_widths(&mut output, &plan);
+
+    for observation in &site.observations {
+        let mac = observation.wifi.mac.as_str();
+
+        output.push_str(&format!(
+            "\nSSID: {ssid}\nMAC: {mac}\nChannel: {channel}\nSeen at {seen} of {points} points, signal max {max:.0} dBm, mean {mean:.1} dBm, 10th percentile {low:.0} dBm\n",
+            ssid = observation.wifi.ssid,
+            mac = observation.wifi.mac,
+            channel = observation.wifi.channel,
+            seen = observation.seen(),
+            points = site.points,
+            max = observation.max(),
+            mean = observation.mean(),
+            low = observation.percentile(10.0),
+        ));
+
+        match suggestions.get(mac) {
+            Some(Suggestion::Suggestion5g(sug)) => {
+                let block = |center: u8, mhz: u16| match domain.max_eirp(Band::Ghz5, center, mhz) {
+                    Some(eirp) => format!("{} (up to {} dBm)", center, eirp),
+                    None => "none".to_string(),
+                };
+
+                output.push_str(&format!(
+                    "Suggestion:\n\t20MHz: {}\n\tDFS 20MHz: {}\n\t40MHz: {}\n\tDFS 40MHz: {}\n\t80MHz: {}\n\tDFS 80MHz: {}\n\tDFS 160MHz: {}\n",
+                    block(sug.ndfs_20, 20),
+                    block(sug.dfs_20, 20),
+                    block(sug.ndfs_40, 40),
+                    block(sug.dfs_40, 40),
+                    block(sug.ndfs_80, 80),
+                    block(sug.dfs_80, 80),
+                    block(sug.dfs_160, 160),
+                ));
+            }
+            Some(suggestion) => output.push_str(&format!("Suggestion: {:?}\n", suggestion)),
+            None => output.push_str("No suggestion available.\n"),
+        }
+
+        if let Some(rationale) = plan.rationale.get(mac) {
+            output.push_str(&format!("{}\n", rationale));
+        }
+
+        if let Some(power) = plan.power.get(mac) {
+            output.push_str(&format!("{}\n", power));
+        }
+    }
+
+    Some(output)
+}
+
+#[derive(Serialize)]
+struct RawSiteNetwork<'a> {
+    ssid: &'a str,
+    mac: &'a str,
+    channel: u8,
+    seen: usize,
+    max_signal: f32,
+    mean_signal: f32,
+    percentile_10_signal: f32,
+    suggestion: Option<Suggestion>,
+    rationale: Option<&'a Rationale>,
+    power: Option<&'a Power>,
+}
+
+#[derive(Serialize)]
+struct RawSite<'a> {
+    local: &'a str,
+    points: usize,
+    country: Option<&'a str>,
+    seed: u64,
+    width_2_4_ghz: Option<&'a WidthAdvice>,
+    width_5_ghz: Option<&'a WidthAdvice>,
+    width_6_ghz: Option<&'a WidthAdvice>,
+    networks: Vec<RawSiteNetwork<'a>>,
+}
+
+/// Same as `site_plan` in a json format.
+#[get("/site/<local>/plan/raw")]
+pub async fn site_plan_raw(local: &str, config: &State<Config>) -> Option<String> {
+    let (site, plan) = get_site(local, config)?;
+    let suggestions = suggestions(&plan);
+
+    let output = RawSite {
+        local: &site.local,
+        points: site.points,
+        country: plan.country.as_deref(),
+        seed: plan.seed,
+        width_2_4_ghz: plan.width_2_4_ghz.as_ref(),
+        width_5_ghz: plan.width_5_ghz.as_ref(),
+        width_6_ghz: plan.width_6_ghz.as_ref(),
+        networks: site
+            .observations
+            .iter()
+            .map(|observation| RawSiteNetwork {
+                ssid: &observation.wifi.ssid,
+                mac: &observation.wifi.mac,
+                channel: observation.wifi.channel,
+                seen: observation.seen(),
+                max_signal: observation.max(),
+                mean_signal: observation.mean(),
+                percentile_10_signal: observation.percentile(10.0),
+                suggestion: suggestions.get(observation.wifi.mac.as_str()).copied(),
+                rationale: plan.rationale.get(&observation.wifi.mac),
+                power: plan.power.get(&observation.wifi.mac),
+            })
+            .collect(),
+    };
+
+    serde_json::to_string(&output).ok()
+}
+
 /// Serves a file requested by the user.
 #[get("/<id>")]
 pub async fn serve(id: ReadingID<'_>) -> Option<File> {
@@ -193,6 +578,30 @@ pub async fn default_route() -> &'static str {
         GET     /<ssid>/<mac>/raw
             - Retrieves the latest suggestion available to the given ssid/mac device in a json format.
 
+        GET     /<file_id>/survey
+            - Lists how busy each channel was and its noise floor, if the reading carries survey data.
+
+        GET     /<file_id>/widths
+            - Recommends the width the networks of each band should run at, and why.
+
+        GET     /<file_id>/matrix.csv
+            - Exports the interference every network puts on every other, one line per pair.
+
+        GET     /<file_id>/matrix.json
+            - Same as above in a json format, as a matrix.
+
+        GET     /diff/<file_id>/<file_id>?threshold=<dB>
+            - Lists the networks that appeared, vanished or changed between two readings, signal changes under the threshold left out.
+
+        GET     /diff/<file_id>/<file_id>/raw?threshold=<dB>
+            - Same as above in a json format.
+
+        GET     /site/<local>/plan
+            - Plans all the readings uploaded for the same local at once, the worst spot of the site deciding.
+
+        GET     /site/<local>/plan/raw
+            - Same as above in a json format.
+
         GET     /<file_id>/<ssid>/<mac>
             - Retrieves a suggestion for a specific ssid/mac device in a file specified by the client.
         
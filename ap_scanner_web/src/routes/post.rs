@@ -18,7 +18,7 @@ pub async fn upload(data: Data<'_>) -> std::io::Result<String> {
 
     println!(
         "Received a reading with {} total elements. Saving to \"{:?}\"",
-        reading.wifi_2_4_ghz.len() + reading.wifi_5_ghz.len(),
+        reading.wifi_2_4_ghz.len() + reading.wifi_5_ghz.len() + reading.wifi_6_ghz.len(),
         &id.path()
     );
 